@@ -1,6 +1,7 @@
-use eth_merkle_tree::graph::visualizer::graphviz;
+use eth_merkle_tree::graph::svg::save_svg;
 use eth_merkle_tree::tree::MerkleTree;
-use eth_merkle_tree::utils::keccak::keccak256;
+use eth_merkle_tree::utils::hash::Hash32;
+use eth_merkle_tree::utils::keccak::leaf_hash;
 use std::error::Error;
 
 fn main() {
@@ -30,11 +31,11 @@ fn main() {
     let root = get_root(&tree).expect("Unable to access root");
     println!("Root: {}", root);
     visualize(&tree);
-    let target_hash = keccak256(&data[0]).expect("Keccak error.");
+    let target_hash = leaf_hash(&data[0]).expect("Keccak error.");
 
     // Getting proof
     if let Some(index) = tree.locate_leaf(&target_hash) {
-        let proof = tree.generate_proof(index);
+        let proof = tree.generate_proof(index).expect("Proof error.");
         println!(
             "Merkle proof for {}: {:?} index: {}",
             target_hash, proof, index
@@ -48,13 +49,14 @@ fn create_tree(data: &[String]) -> MerkleTree {
     MerkleTree::new(data).expect("Tree creation error.")
 }
 
-fn get_root(tree: &MerkleTree) -> Result<String, Box<dyn Error>> {
+fn get_root(tree: &MerkleTree) -> Result<Hash32, Box<dyn Error>> {
     match &tree.root {
-        Some(root) => Ok(root.data.clone()),
+        Some(root) => Ok(root.data),
         None => Err(Box::from("No root found")),
     }
 }
 
 fn visualize(tree: &MerkleTree) {
-    graphviz(tree).expect("Visualization Error!");
+    save_svg(tree, "merkle_tree.svg").expect("Visualization Error!");
+    println!("SVG file saved to merkle_tree.svg");
 }
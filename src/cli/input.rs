@@ -0,0 +1,318 @@
+//! # Input
+//!
+//! Reads the leaves of a tree from a leaf file: plain lines, CSV with a header row, JSON Lines, or a JSON array.
+//!
+//! Each leaf is read as a row of columns. Columns are selected by header name or object key, or by
+//! zero-based position, and are kept in the order they're selected in.
+//!
+//! CSV fields and JSON strings are values already, so they're kept as written: only CSV fields of columns
+//! other than `string` ones are trimmed.
+
+use clap::ValueEnum;
+use csv::{ReaderBuilder, StringRecord, Trim};
+use eth_merkle_tree::utils::abi::{split_values, value_from_json, AbiType};
+use eth_merkle_tree::utils::errors::MerkleError;
+use serde_json::Value;
+use std::path::Path;
+
+/// The format of a leaf file.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Guess the format from the file extension and contents
+    Auto,
+    /// One leaf per line, columns separated by commas
+    Lines,
+    /// CSV with a header row
+    Csv,
+    /// One JSON object or array per line
+    Jsonl,
+    /// A JSON array of objects or arrays
+    Json,
+    /// A tree dump written by `build` or by `StandardMerkleTree.dump()`
+    Dump,
+}
+
+impl InputFormat {
+    /// Resolves `Auto` from the extension of `path` and the first character of `contents`.
+    pub fn detect(self, path: &Path, contents: &str) -> Self {
+        if self != InputFormat::Auto {
+            return self;
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match (extension, contents.trim_start().chars().next()) {
+            ("csv", _) => InputFormat::Csv,
+            ("jsonl" | "ndjson", _) => InputFormat::Jsonl,
+            (_, Some('{')) => InputFormat::Dump,
+            (_, Some('[')) => InputFormat::Json,
+            _ => InputFormat::Lines,
+        }
+    }
+}
+
+/// Reads the rows of a leaf file.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the leaf file.
+/// * `format` - The format of the file, other than `Auto` and `Dump`.
+/// * `columns` - The columns forming each leaf. Every column is used when empty, except for objects,
+///   which need their keys to be selected.
+/// * `types` - The Solidity type of each selected column, empty for raw leaves. CSV fields of `string`
+///   columns aren't trimmed.
+///
+/// # Errors
+///
+/// * The file isn't valid CSV or JSON.
+/// * A row lacks a selected column, reported with the index of the row.
+///
+pub fn read_rows(
+    contents: &str,
+    format: InputFormat,
+    columns: &[String],
+    types: &[String],
+) -> Result<Vec<Vec<String>>, MerkleError> {
+    match format {
+        InputFormat::Csv => read_csv(contents, columns, types),
+        InputFormat::Jsonl => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                let row = serde_json::from_str(line)
+                    .map_err(|e| MerkleError::InvalidInput(format!("line {}: {}", index + 1, e)))?;
+                select_json(index, &row, columns)
+            })
+            .collect(),
+        InputFormat::Json => {
+            let rows: Vec<Value> = serde_json::from_str(contents)
+                .map_err(|e| MerkleError::InvalidInput(e.to_string()))?;
+            rows.iter()
+                .enumerate()
+                .map(|(index, row)| select_json(index, row, columns))
+                .collect()
+        }
+        _ => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                if columns.is_empty() {
+                    return Ok(vec![line.to_string()]);
+                }
+                let values = split_values(line)?;
+                select(index, columns, |column| {
+                    column
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| values.get(i))
+                        .cloned()
+                })
+            })
+            .collect(),
+    }
+}
+
+fn read_csv(
+    contents: &str,
+    columns: &[String],
+    types: &[String],
+) -> Result<Vec<Vec<String>>, MerkleError> {
+    let mut reader = ReaderBuilder::new()
+        .trim(Trim::Headers)
+        .from_reader(contents.as_bytes());
+    let is_string = |position: usize| {
+        types
+            .get(position)
+            .is_some_and(|ty| matches!(ty.parse(), Ok(AbiType::String)))
+    };
+    let headers = reader
+        .headers()
+        .map_err(|e| MerkleError::InvalidInput(e.to_string()))?
+        .clone();
+    let all_columns: Vec<String> = (0..headers.len()).map(|i| i.to_string()).collect();
+    let columns = if columns.is_empty() {
+        &all_columns
+    } else {
+        columns
+    };
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record.map_err(|e| MerkleError::LeafEncoding(index, e.to_string()))?;
+            let row = select(index, columns, |column| {
+                csv_field(&headers, &record, column).map(str::to_string)
+            })?;
+            Ok(row
+                .into_iter()
+                .enumerate()
+                .map(|(position, field)| {
+                    if is_string(position) {
+                        field
+                    } else {
+                        field.trim().to_string()
+                    }
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Returns the field of a CSV column, given by header name or by position.
+fn csv_field<'r>(
+    headers: &StringRecord,
+    record: &'r StringRecord,
+    column: &str,
+) -> Option<&'r str> {
+    let position = headers
+        .iter()
+        .position(|header| header == column)
+        .or_else(|| column.parse().ok())?;
+    record.get(position)
+}
+
+fn select_json(index: usize, row: &Value, columns: &[String]) -> Result<Vec<String>, MerkleError> {
+    match row {
+        Value::Object(_) if columns.is_empty() => Err(MerkleError::LeafEncoding(
+            index,
+            String::from("Select the keys of object rows with --columns"),
+        )),
+        Value::Object(fields) => select(index, columns, |column| {
+            fields.get(column).map(value_from_json).transpose().ok()?
+        }),
+        Value::Array(fields) if columns.is_empty() => fields.iter().map(value_from_json).collect(),
+        Value::Array(fields) => select(index, columns, |column| {
+            let field = fields.get(column.parse::<usize>().ok()?)?;
+            value_from_json(field).ok()
+        }),
+        _ => Ok(vec![value_from_json(row)?]),
+    }
+}
+
+/// Picks the selected columns of a row, failing on the first missing one.
+fn select(
+    index: usize,
+    columns: &[String],
+    field: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<String>, MerkleError> {
+    columns
+        .iter()
+        .map(|column| {
+            field(column).ok_or_else(|| {
+                MerkleError::LeafEncoding(index, format!("Missing column '{}'", column))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let contents = "name,address,amount,note\n\
+            alice,0x1111111111111111111111111111111111111111,100,\"first, early\"\n\
+            bob, 0x2222222222222222222222222222222222222222 ,200,\n";
+        let rows = read_rows(contents, InputFormat::Csv, &strings(&["address", "2"]), &[])
+            .expect("Failed to read rows");
+        assert_eq!(
+            rows,
+            vec![
+                strings(&["0x1111111111111111111111111111111111111111", "100"]),
+                strings(&["0x2222222222222222222222222222222222222222", "200"]),
+            ]
+        );
+        let rows = read_rows(contents, InputFormat::Csv, &[], &[]).expect("Failed to read rows");
+        assert_eq!(rows[0][3], "first, early");
+        assert!(matches!(
+            read_rows(contents, InputFormat::Csv, &strings(&["email"]), &[]),
+            Err(MerkleError::LeafEncoding(0, _))
+        ));
+    }
+
+    #[test]
+    fn test_read_keeps_string_values() {
+        let csv = "address,name\n 0x1111111111111111111111111111111111111111 , \" alice \" \n";
+        let types = strings(&["address", "string"]);
+        let rows = read_rows(csv, InputFormat::Csv, &[], &types).expect("Failed to read rows");
+        assert_eq!(
+            rows[0],
+            strings(&[
+                "0x1111111111111111111111111111111111111111",
+                " \" alice \" "
+            ])
+        );
+        let rows = read_rows(
+            csv,
+            InputFormat::Csv,
+            &strings(&["1"]),
+            &strings(&["string"]),
+        )
+        .expect("Failed to read rows");
+        assert_eq!(rows[0], strings(&[" \" alice \" "]));
+
+        let json = "[{\"name\": \" [alice] \"}, {\"name\": \"\\\"bob\\\"\"}]";
+        let rows = read_rows(json, InputFormat::Json, &strings(&["name"]), &[])
+            .expect("Failed to read rows");
+        assert_eq!(rows, vec![strings(&[" [alice] "]), strings(&["\"bob\""])]);
+    }
+
+    #[test]
+    fn test_read_json() {
+        let jsonl =
+            "{\"address\": \"0x1111111111111111111111111111111111111111\", \"amount\": 100}\n\
+            \n\
+            {\"address\": \"0x2222222222222222222222222222222222222222\", \"amount\": \"200\"}\n";
+        let columns = strings(&["address", "amount"]);
+        let rows =
+            read_rows(jsonl, InputFormat::Jsonl, &columns, &[]).expect("Failed to read rows");
+        assert_eq!(
+            rows[0],
+            strings(&["0x1111111111111111111111111111111111111111", "100"])
+        );
+        assert_eq!(rows[1][1], "200");
+        assert!(read_rows(jsonl, InputFormat::Jsonl, &[], &[]).is_err());
+
+        let json = "[[\"0xabc\", [1, 2]], [\"0xdef\", [3]]]";
+        let rows = read_rows(json, InputFormat::Json, &[], &[]).expect("Failed to read rows");
+        assert_eq!(rows[1], strings(&["0xdef", "[3]"]));
+        let rows =
+            read_rows(json, InputFormat::Json, &strings(&["1"]), &[]).expect("Failed to read rows");
+        assert_eq!(rows[0], strings(&["[1, 2]"]));
+        assert!(read_rows("[", InputFormat::Json, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let contents = "0xabc, 1\n\n0xdef, 2\n";
+        let rows = read_rows(contents, InputFormat::Lines, &[], &[]).expect("Failed to read rows");
+        assert_eq!(rows, vec![strings(&["0xabc, 1"]), strings(&["0xdef, 2"])]);
+        let rows = read_rows(contents, InputFormat::Lines, &strings(&["1", "0"]), &[])
+            .expect("Failed to read rows");
+        assert_eq!(rows[1], strings(&["2", "0xdef"]));
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let detect =
+            |path: &str, contents: &str| InputFormat::Auto.detect(Path::new(path), contents);
+        assert_eq!(detect("leaves.csv", "a,b"), InputFormat::Csv);
+        assert_eq!(detect("leaves.jsonl", "{}"), InputFormat::Jsonl);
+        assert_eq!(
+            detect("tree.json", " {\"format\": \"standard-v1\"}"),
+            InputFormat::Dump
+        );
+        assert_eq!(detect("leaves.json", "[]"), InputFormat::Json);
+        assert_eq!(detect("example.txt", "0xabc"), InputFormat::Lines);
+        assert_eq!(
+            InputFormat::Csv.detect(Path::new("leaves.txt"), "a"),
+            InputFormat::Csv
+        );
+    }
+}
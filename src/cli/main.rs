@@ -2,31 +2,337 @@
 //!
 //! Command line interface for the Ethereum Merkle tree library.
 
-use clap::Parser;
+mod input;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::*;
-use eth_merkle_tree::tree::MerkleTree;
-use eth_merkle_tree::utils::keccak::keccak256;
+use eth_merkle_tree::graph::dot::to_dot_highlighted;
+use eth_merkle_tree::graph::highlight::{ProofHighlight, ProofRole};
+use eth_merkle_tree::graph::html::to_html_highlighted;
+use eth_merkle_tree::graph::mermaid::to_mermaid_highlighted;
+use eth_merkle_tree::graph::svg::to_svg_highlighted;
+use eth_merkle_tree::tree::{
+    DirectedProof, MerkleTree, PairHashing, Side, SolidityOptions, TreeDump, TreeLayout,
+};
+use eth_merkle_tree::utils::abi::split_values;
+use eth_merkle_tree::utils::errors::MerkleError;
+use eth_merkle_tree::utils::hash::Hash32;
+use eth_merkle_tree::utils::hasher::{Blake2b, Blake3, Keccak256, MerkleHasher, Sha256};
+use eth_merkle_tree::utils::verify::{
+    check_positional_proof_with_hasher, check_proof_with_hasher, ProofCheck,
+};
+use input::{read_rows, InputFormat};
 use petgraph::prelude::*;
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "eth-merkle-tree-rs", about = "Ethereum Merkle Tree Tool")]
 pub struct Opt {
-    /// Activate tree visualization
-    #[arg(short, long)]
-    visualize: bool,
+    #[command(subcommand)]
+    command: Command,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Hash function of the tree
+    #[arg(long, global = true, value_enum, default_value_t = HashFunction::Keccak256)]
+    hash: HashFunction,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum HashFunction {
+    Keccak256,
+    Sha256,
+    Blake2b,
+    Blake3,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Colored text for humans
+    Text,
+    /// JSON for scripts
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Diagram {
+    /// Graphviz DOT text
+    Dot,
+    /// SVG image, drawn without Graphviz
+    Svg,
+    /// Mermaid `graph TD` block, for Markdown documents
+    Mermaid,
+    /// Self-contained HTML page with foldable subtrees
+    Html,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Build a tree from a leaf file and write its dump
+    #[command(alias = "dump")]
+    Build {
+        #[command(flatten)]
+        tree: TreeArgs,
+
+        /// Write the dump to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the proof of one or more leaves
+    Proof {
+        #[command(flatten)]
+        tree: TreeArgs,
+
+        /// Leaves to prove, written like the lines of the leaf file
+        #[arg(required = true)]
+        leaves: Vec<String>,
+    },
+    /// Write the claims file of a distribution, with the proof of every address
+    Proofs {
+        #[command(flatten)]
+        tree: TreeArgs,
+
+        /// Write the claims to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Zero-based position of the address among the `--types` columns, when several are addresses
+        #[arg(long, value_name = "INDEX", requires = "amount_column")]
+        address_column: Option<usize>,
+
+        /// Zero-based position of the amount among the `--types` columns, when several are `uintN`
+        #[arg(long, value_name = "INDEX", requires = "address_column")]
+        amount_column: Option<usize>,
+    },
+    /// Verify a proof against a root
+    Verify(VerifyArgs),
+    /// Write a Solidity contract verifying proofs of a tree, with its root embedded
+    Contract {
+        #[command(flatten)]
+        tree: TreeArgs,
+
+        /// Name of the contract
+        #[arg(long, default_value = "MerkleVerifier")]
+        name: String,
+
+        /// Add a `claim` function accepting each leaf once
+        #[arg(long)]
+        claim: bool,
+
+        /// Write the contract to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the depth, leaf count and root of a tree
+    Inspect {
+        #[command(flatten)]
+        tree: TreeArgs,
+    },
+    /// Print the structure of a tree, or draw it as a diagram
+    Visualize {
+        #[command(flatten)]
+        tree: TreeArgs,
+
+        /// Draw the tree as a diagram instead of printing it
+        #[arg(long, value_enum)]
+        diagram: Option<Diagram>,
+
+        /// Write the diagram to this file instead of stdout
+        #[arg(short, long, requires = "diagram")]
+        output: Option<PathBuf>,
+
+        /// Highlight the proof of this leaf, written like the lines of the leaf file
+        #[arg(long)]
+        highlight: Option<String>,
+
+        #[command(flatten)]
+        view: ViewArgs,
+    },
+}
+
+#[derive(Args, Debug)]
+struct TreeArgs {
+    /// Leaf file (lines, CSV, JSON Lines or a JSON array), or a tree dump
+    input: PathBuf,
+
+    /// Format of the input file
+    #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+    input_format: InputFormat,
+
+    /// Columns forming each leaf, by header name, object key or zero-based position
+    #[arg(long, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Solidity type of each leaf column, like `address,uint256`, to build an OpenZeppelin StandardMerkleTree
+    #[arg(long, value_delimiter = ',')]
+    types: Vec<String>,
+
+    /// Build the leaf file as an OpenZeppelin StandardMerkleTree
+    #[arg(long)]
+    standard: bool,
+
+    /// Hash pairs left to right instead of sorting them, like the ETH2 deposit contract
+    #[arg(long)]
+    positional: bool,
+}
+
+/// Options of the tree printed by `visualize`.
+#[derive(Args, Debug)]
+struct ViewArgs {
+    /// Shorten hashes to their first and last DIGITS hex digits, like `0x1234…abcd`
+    #[arg(long, value_name = "DIGITS", value_parser = clap::value_parser!(u8).range(1..=32), conflicts_with = "diagram")]
+    truncate: Option<u8>,
+
+    /// Show the level, counted from the root, and the graph index of each node
+    #[arg(long, conflicts_with = "diagram")]
+    positions: bool,
+
+    /// Show the value each leaf was built from
+    #[arg(long, conflicts_with = "diagram")]
+    values: bool,
 
-    /// Provide the leaf for proof retrieval.
-    #[arg(short, long)]
-    proof: Option<String>,
+    /// Print at most this many levels below the first node
+    #[arg(long, conflicts_with = "diagram")]
+    max_depth: Option<usize>,
 
-    /// Directory Path
-    dir: PathBuf,
+    /// Print only the subtree below the node at this graph index
+    #[arg(long, value_name = "INDEX", conflicts_with = "diagram")]
+    subtree: Option<usize>,
 }
-/// Takes user-provided arguments, constructs a Merkle Tree, and prints the results to the console.
+
+/// A tree printed by `visualize`, with the options it's printed with.
+struct TreeView<'a, H> {
+    tree: &'a MerkleTree<H>,
+    highlight: &'a ProofHighlight,
+    args: &'a ViewArgs,
+    /// The graph index of the first node to print.
+    start: usize,
+    /// The level of the first node, the root being at level 0.
+    level: usize,
+    /// The values each leaf was built from, by graph index.
+    values: HashMap<usize, String>,
+}
+
+impl<'a, H: MerkleHasher> TreeView<'a, H> {
+    /// # Errors
+    ///
+    /// * `--subtree` isn't the index of a node.
+    fn new(
+        tree: &'a MerkleTree<H>,
+        highlight: &'a ProofHighlight,
+        args: &'a ViewArgs,
+    ) -> Result<Self, MerkleError> {
+        let start = args.subtree.unwrap_or(tree.root_index());
+        if start >= tree.graph.node_count() {
+            return Err(MerkleError::InvalidInput(format!(
+                "the tree has no node at index {}",
+                start
+            )));
+        }
+        let level = std::iter::successors(Some(start), |node| tree.parent(*node)).count() - 1;
+        let values = tree
+            .leaf_values()
+            .map(|(index, value)| (index, value.join(", ")))
+            .collect();
+        Ok(TreeView {
+            tree,
+            highlight,
+            args,
+            start,
+            level,
+            values,
+        })
+    }
+
+    /// Returns the children of the node `depth` levels below the first one, none past `--max-depth`.
+    fn children(&self, node: usize, depth: usize) -> Vec<usize> {
+        if self
+            .args
+            .max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            return Vec::new();
+        }
+        self.tree.children(node)
+    }
+
+    /// Returns the number of leaves below `node`, itself if it's a leaf.
+    fn leaves_below(&self, node: usize) -> usize {
+        let children = self.tree.children(node);
+        if children.is_empty() {
+            return 1;
+        }
+        children.iter().map(|child| self.leaves_below(*child)).sum()
+    }
+
+    /// Returns the hash of `node`, shortened with `--truncate`.
+    fn hash(&self, node: usize) -> String {
+        let hash = self.tree.graph[NodeIndex::new(node)];
+        match self.args.truncate {
+            Some(digits) => hash.abbreviate(digits.into()),
+            None => hash.to_string(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Root of the tree
+    #[arg(long, required_unless_present = "file")]
+    root: Option<String>,
+
+    /// Leaf being proven, written like the lines of the leaf file
+    #[arg(long, required_unless_present = "file")]
+    leaf: Option<String>,
+
+    /// Sibling hashes of the proof, comma-separated or repeated
+    #[arg(long, value_delimiter = ',')]
+    proof: Vec<String>,
+
+    /// JSON file holding the `root`, `leaf` and `proof` instead of arguments
+    #[arg(long, conflicts_with_all = ["root", "leaf", "proof"])]
+    file: Option<PathBuf>,
+
+    /// Hash the leaf as an OpenZeppelin StandardMerkleTree leaf
+    #[arg(long)]
+    standard: bool,
+
+    /// Solidity types of the leaf values, like `address,uint256`, for trees built with a leaf encoding
+    #[arg(long, value_delimiter = ',')]
+    types: Vec<String>,
+
+    /// Verify a proof of a tree built with `--positional`, hashing each sibling on its side
+    #[arg(long)]
+    positional: bool,
+
+    /// Side of each sibling of a positional proof, `left` or `right`, comma-separated or repeated
+    #[arg(long, value_delimiter = ',', value_parser = parse_side, conflicts_with = "file", requires = "positional")]
+    sides: Vec<Side>,
+}
+
+/// The content of the file given to `verify --file`. Entries of `proof --format json` have this shape.
+#[derive(Deserialize, Debug)]
+struct ProofFile {
+    root: String,
+    leaf: String,
+    proof: Vec<String>,
+    /// The sides of the siblings, only present for positional trees.
+    #[serde(default)]
+    sides: Vec<Side>,
+}
+
+/// Takes user-provided arguments, runs the requested command, and prints the results to the console.
 ///
 /// # Examples
 ///
@@ -34,80 +340,497 @@ pub struct Opt {
 ///
 /// When run from the command line:
 /// ```text
-/// $ emtr ./example.txt --proof 0x7b95d138cD923476b6e697391DD2aA01D15BAB27 -v
+/// $ emtr build ./example.txt --output tree.json
+/// $ emtr proof tree.json 0x7b95d138cD923476b6e697391DD2aA01D15BAB27
+/// $ emtr inspect tree.json
 /// ```
 ///
-/// # Panics
-///
-/// When given an input in the incorrect format.
+/// # Errors
 ///
-/// When provided with an invalid argument.
+/// Prints the error and exits with a non-zero status when given an input in the incorrect format,
+/// when the requested leaf isn't part of the tree, or when a proof doesn't verify.
 ///
-fn main() {
+fn main() -> ExitCode {
     let opt = Opt::parse();
-    let tree = create_tree(&opt.dir)
-        .unwrap_or_else(|_| panic!("{}", "Merkle Tree Creation Error".bright_red()));
-    let root = get_root(&tree).unwrap_or_else(|_| panic!("{}", "No root found".bright_red()));
-    println!("{}: {}", "Merkle Root".bright_blue(), root.bright_green());
-    if let Some(proof_value) = &opt.proof {
-        let target_hash = keccak256(proof_value)
-            .unwrap_or_else(|_| panic!("{}", "Keccak256 Error!".bright_red()));
-        if let Some(index) = tree.locate_leaf(&target_hash) {
-            let proof = tree.generate_proof(index);
+    if opt.no_color {
+        colored::control::set_override(false);
+    }
+    let result = match opt.hash {
+        HashFunction::Keccak256 => run::<Keccak256>(&opt),
+        HashFunction::Sha256 => run::<Sha256>(&opt),
+        HashFunction::Blake2b => run::<Blake2b>(&opt),
+        HashFunction::Blake3 => run::<Blake3>(&opt),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            match opt.format {
+                Format::Text => eprintln!("{}", e.to_string().bright_red()),
+                Format::Json => eprintln!("{}", json!({ "error": e.to_string() })),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the command requested by `opt`.
+///
+/// With `--format json` every command prints a single JSON document:
+///
+/// * `build`: the tree dump, or `{"root", "output"}` when written to a file.
+/// * `proof`: an array of `{"root", "leaf", "leafHash", "index", "proof"}`, one per leaf, with `"sides"`
+///   and `"pathBits"` for positional trees.
+/// * `proofs`: the claims file, or `{"root", "output"}` when written to a file.
+/// * `verify`: `{"valid", "root", "computedRoot", "leaf", "proof"}`, with `"sides"` for positional proofs.
+/// * `contract`: `{"root", "contract"}`, or `{"root", "output"}` when written to a file.
+/// * `inspect`: `{"root", "leafCount", "depth", "layout", "pairHashing"}`.
+/// * `visualize`: nested `{"hash", "children"}` nodes, starting from the root or the `--subtree` node, with
+///   `"role"` for the nodes of the `--highlight` proof, `"level"` and `"index"` with `--positions`, `"value"`
+///   for leaves with `--values`, and `"leavesBelow"` for nodes cut by `--max-depth`. With `--diagram`, `{"root", "diagram"}`,
+///   or `{"root", "output"}` when written to a file.
+///
+/// # Returns
+///
+/// * `Ok(false)` when a proof doesn't verify, `Ok(true)` otherwise.
+fn run<H: MerkleHasher>(opt: &Opt) -> Result<bool, MerkleError> {
+    let json_output = opt.format == Format::Json;
+    match &opt.command {
+        Command::Build { tree, output } => {
+            let tree = read_tree::<H>(tree)?;
+            let json = tree.dump().to_json()?;
+            match output {
+                Some(path) => {
+                    fs::write(path, json + "\n")?;
+                    if json_output {
+                        println!("{:#}", json!({ "root": get_root(&tree)?, "output": path }));
+                    } else {
+                        eprintln!(
+                            "{} {}",
+                            "Tree dump written to".bright_blue(),
+                            path.display().to_string().bright_green()
+                        );
+                    }
+                }
+                None => println!("{}", json),
+            }
+        }
+        Command::Proof { tree, leaves } => {
+            let tree = read_tree::<H>(tree)?;
+            let root = get_root(&tree)?;
+            let proofs = leaves
+                .iter()
+                .map(|leaf| leaf_proof(&tree, leaf))
+                .collect::<Result<Vec<LeafProof>, _>>()?;
+            let positional = tree.pair_hashing == PairHashing::Positional;
+            if json_output {
+                let proofs: Vec<Value> = proofs
+                    .iter()
+                    .map(|p| {
+                        let mut entry = json!({
+                            "root": root,
+                            "leaf": p.leaf,
+                            "leafHash": p.leaf_hash,
+                            "index": p.index,
+                            "proof": p.proof.siblings,
+                        });
+                        if positional {
+                            entry["sides"] = json!(p.proof.sides);
+                            entry["pathBits"] = json!(p.proof.path_bits());
+                        }
+                        entry
+                    })
+                    .collect();
+                println!("{:#}", Value::Array(proofs));
+                return Ok(true);
+            }
+            for p in &proofs {
+                let proof: Vec<String> = p.proof.siblings.iter().map(Hash32::to_string).collect();
+                println!(
+                    "{} '{}'= [{}] \n {} = {}",
+                    "Merkle proof for".bright_blue(),
+                    p.leaf.bright_blue(),
+                    proof.join(", ").bright_green(),
+                    "index".bright_blue(),
+                    p.index.to_string().bright_green()
+                );
+                if positional {
+                    let sides: Vec<String> = p.proof.sides.iter().map(side_name).collect();
+                    println!(
+                        " {} = [{}]",
+                        "sides".bright_blue(),
+                        sides.join(", ").bright_green()
+                    );
+                }
+            }
+        }
+        Command::Proofs {
+            tree,
+            output,
+            address_column,
+            amount_column,
+        } => {
+            let tree = read_tree::<H>(tree)?;
+            let distribution = match address_column.zip(*amount_column) {
+                Some((address, amount)) => tree.distribution_with_columns(address, amount)?,
+                None => tree.distribution()?,
+            };
+            let json = distribution.to_json()?;
+            match output {
+                Some(path) => {
+                    fs::write(path, json + "\n")?;
+                    if json_output {
+                        println!("{:#}", json!({ "root": get_root(&tree)?, "output": path }));
+                    } else {
+                        eprintln!(
+                            "{} {}",
+                            "Claims written to".bright_blue(),
+                            path.display().to_string().bright_green()
+                        );
+                    }
+                }
+                None => println!("{}", json),
+            }
+        }
+        Command::Verify(args) => {
+            let proof_file = read_proof(args)?;
+            let check = verify::<H>(args, &proof_file)?;
+            let valid = check.is_valid();
+            if json_output {
+                let mut output = json!({
+                    "valid": valid,
+                    "root": proof_file.root,
+                    "computedRoot": check.computed_root(),
+                    "leaf": proof_file.leaf,
+                    "proof": proof_file.proof,
+                });
+                if args.positional {
+                    output["sides"] = json!(proof_file.sides);
+                }
+                println!("{:#}", output);
+            } else if valid {
+                println!("{}", "Proof is valid".bright_green());
+            } else {
+                println!("{}", "Proof is invalid".bright_red());
+                println!(
+                    "{}: {}",
+                    "Computed Root".bright_blue(),
+                    check.computed_root().to_string().bright_red()
+                );
+            }
+            return Ok(valid);
+        }
+        Command::Contract {
+            tree,
+            name,
+            claim,
+            output,
+        } => {
+            let tree = read_tree::<H>(tree)?;
+            let root = get_root(&tree)?;
+            let contract = tree.solidity_verifier(&SolidityOptions {
+                contract_name: name.clone(),
+                claim: *claim,
+            })?;
+            match output {
+                Some(path) => {
+                    fs::write(path, contract)?;
+                    if json_output {
+                        println!("{:#}", json!({ "root": root, "output": path }));
+                    } else {
+                        eprintln!(
+                            "{} {}",
+                            "Contract written to".bright_blue(),
+                            path.display().to_string().bright_green()
+                        );
+                    }
+                }
+                None if json_output => {
+                    println!("{:#}", json!({ "root": root, "contract": contract }))
+                }
+                None => print!("{}", contract),
+            }
+        }
+        Command::Inspect { tree } => {
+            let tree = read_tree::<H>(tree)?;
+            let root = get_root(&tree)?;
+            let layout = format!("{:?}", tree.layout);
+            let pair_hashing = format!("{:?}", tree.pair_hashing);
+            if json_output {
+                println!(
+                    "{:#}",
+                    json!({
+                        "root": root,
+                        "leafCount": tree.leaf_count(),
+                        "depth": tree.depth(),
+                        "layout": layout.to_lowercase(),
+                        "pairHashing": pair_hashing.to_lowercase(),
+                    })
+                );
+                return Ok(true);
+            }
             println!(
-                "{} '{}'= [{}] \n {} = {}",
-                "Merkle proof for".bright_blue(),
-                proof_value.bright_blue(),
-                proof.join(", ").bright_green(),
-                "index".bright_blue(),
-                index.to_string().bright_green()
+                "{}: {}",
+                "Merkle Root".bright_blue(),
+                root.to_string().bright_green()
+            );
+            println!(
+                "{}: {}",
+                "Leaves".bright_blue(),
+                tree.leaf_count().to_string().bright_green()
+            );
+            println!(
+                "{}: {}",
+                "Depth".bright_blue(),
+                tree.depth().to_string().bright_green()
+            );
+            println!("{}: {}", "Layout".bright_blue(), layout.bright_green());
+            println!(
+                "{}: {}",
+                "Pair hashing".bright_blue(),
+                pair_hashing.bright_green()
             );
-        } else {
-            println!("Leaf not found in the tree");
+        }
+        Command::Visualize {
+            tree,
+            diagram,
+            output,
+            highlight,
+            view,
+        } => {
+            let tree = read_tree::<H>(tree)?;
+            let highlight = match highlight {
+                Some(leaf) => ProofHighlight::new(&tree, leaf_proof(&tree, leaf)?.index)?,
+                None => ProofHighlight::default(),
+            };
+            let Some(diagram) = diagram else {
+                let view = TreeView::new(&tree, &highlight, view)?;
+                if json_output {
+                    println!("{:#}", tree_json(&view, view.start, 0));
+                } else {
+                    print_tree(&view, view.start, 0, "".to_string());
+                }
+                return Ok(true);
+            };
+            let contents = match diagram {
+                Diagram::Dot => to_dot_highlighted(&tree, &highlight),
+                Diagram::Svg => to_svg_highlighted(&tree, &highlight),
+                Diagram::Mermaid => to_mermaid_highlighted(&tree, &highlight),
+                Diagram::Html => to_html_highlighted(&tree, &highlight),
+            };
+            match output {
+                Some(path) => {
+                    fs::write(path, contents)?;
+                    if json_output {
+                        println!("{:#}", json!({ "root": get_root(&tree)?, "output": path }));
+                    } else {
+                        eprintln!(
+                            "{} {}",
+                            "Diagram written to".bright_blue(),
+                            path.display().to_string().bright_green()
+                        );
+                    }
+                }
+                None if json_output => {
+                    println!(
+                        "{:#}",
+                        json!({ "root": get_root(&tree)?, "diagram": contents })
+                    )
+                }
+                None => print!("{}", contents),
+            }
         }
     }
-    if opt.visualize {
-        let root_node = find_root_node(&tree.graph);
-        print_tree(&tree.graph, root_node, "".to_string());
+    Ok(true)
+}
+
+/// A leaf of the tree with its proof.
+struct LeafProof {
+    leaf: String,
+    leaf_hash: Hash32,
+    index: usize,
+    proof: DirectedProof,
+}
+
+/// Returns the leaf hash, the tree index and the proof of `leaf`.
+///
+/// The leaf is written like a line of the leaf file. For a tree built with a leaf encoding,
+/// it holds the comma-separated values of the leaf.
+///
+/// # Errors
+///
+/// * The leaf can't be hashed the way the leaves of the tree were hashed.
+/// * The leaf isn't part of the tree.
+///
+fn leaf_proof<H: MerkleHasher>(tree: &MerkleTree<H>, leaf: &str) -> Result<LeafProof, MerkleError> {
+    let leaf_hash = if tree.leaf_encoding.is_empty() {
+        tree.hash_value(&[leaf])?
+    } else {
+        tree.hash_value(&split_values(leaf)?)?
+    };
+    let index = tree
+        .locate_leaf(&leaf_hash)
+        .ok_or_else(|| MerkleError::LeafNotFound(leaf.to_string()))?;
+    Ok(LeafProof {
+        leaf: leaf.to_string(),
+        leaf_hash,
+        index,
+        proof: tree.generate_directed_proof(index)?,
+    })
+}
+
+/// Reads the proof described by `args`, from the arguments or from a JSON file.
+///
+/// # Errors
+///
+/// * The proof file isn't found or isn't valid JSON.
+///
+fn read_proof(args: &VerifyArgs) -> Result<ProofFile, MerkleError> {
+    match &args.file {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| MerkleError::InvalidInput(e.to_string())),
+        None => Ok(ProofFile {
+            root: args.root.clone().unwrap_or_default(),
+            leaf: args.leaf.clone().unwrap_or_default(),
+            proof: args.proof.clone(),
+            sides: args.sides.clone(),
+        }),
+    }
+}
+
+/// Verifies a proof, hashing the leaf as selected by `args`.
+///
+/// # Errors
+///
+/// * The root, the leaf or a proof hash can't be decoded.
+/// * `--positional` is given with a number of sides that differs from the number of siblings.
+///
+fn verify<H: MerkleHasher>(
+    args: &VerifyArgs,
+    proof_file: &ProofFile,
+) -> Result<ProofCheck, MerkleError> {
+    let ProofFile {
+        root,
+        leaf,
+        proof,
+        sides,
+    } = proof_file;
+    let leaf_hash = if !args.types.is_empty() {
+        H::standard_leaf_hash_typed(&args.types, &split_values(leaf)?)?
+    } else if args.standard {
+        H::standard_leaf_hash(leaf)?
+    } else {
+        H::leaf_hash(leaf)?
+    };
+    if args.positional {
+        check_positional_proof_with_hasher::<H, _>(proof, sides, root, &leaf_hash)
+    } else {
+        check_proof_with_hasher::<H, _>(proof, root, &leaf_hash)
     }
 }
 
 /// Returns a MerkleTree from the provided file path.
 ///
+/// A tree dump, as written by `build` or by OpenZeppelin's `StandardMerkleTree.dump()`, is loaded as is.
+/// Any other file is read as rows of columns, see [`input::read_rows`]. With `--types` each row is
+/// ABI encoded like `StandardMerkleTree.of`, otherwise its columns are joined into a raw leaf string.
 ///
 /// # Arguments
 ///
-/// * `path` - A PathBuf pointing to the location of the file to be processed.
+/// * `args` - The path and format of the file to be processed, and how its rows form leaves.
 ///
 /// # Returns
 ///
-/// * `Result<MerkleTree, Box<dyn Error>>` - A `Result` containing the constructed `MerkleTree`
+/// * `Result<MerkleTree, MerkleError>` - A `Result` containing the constructed `MerkleTree`
 ///   if successful, or an error if the operation fails.
 ///
 /// # Errors
 ///
 /// * The file specified by `path` isn't found or read.
 /// * The file contents aren't in the expected format for constructing a MerkleTree.
+/// * `--columns`, `--types`, `--standard` or `--positional` contradict a tree dump.
 ///
 /// # Example
 ///
 /// ```ignore
-/// let path = PathBuf::from("example.txt");
-/// let tree = create_tree(&path)?;
+/// let tree = read_tree(&args)?;
 /// ```
 ///
-fn create_tree(path: &PathBuf) -> Result<MerkleTree, Box<dyn Error>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut hex_strings: Vec<String> = Vec::new();
-
-    for line in reader.lines() {
-        let line = line?;
-        if !line.trim().is_empty() {
-            hex_strings.push(line);
+fn read_tree<H: MerkleHasher>(args: &TreeArgs) -> Result<MerkleTree<H>, MerkleError> {
+    let contents = fs::read_to_string(&args.input)?;
+    let format = args.input_format.detect(&args.input, &contents);
+    if format == InputFormat::Dump {
+        let tree = MerkleTree::load_with_hasher(TreeDump::from_json(&contents)?)?;
+        check_dump_args(args, &tree)?;
+        return Ok(tree);
+    }
+    let rows = read_rows(&contents, format, &args.columns, &args.types)?;
+    let tree = if !args.types.is_empty() {
+        let types: Vec<&str> = args.types.iter().map(String::as_str).collect();
+        MerkleTree::of_with_hasher(&rows, &types)?
+    } else {
+        let leaves: Vec<String> = rows.iter().map(|row| row.join(", ")).collect();
+        if args.standard {
+            MerkleTree::new_standard_with_hasher(&leaves)?
+        } else {
+            MerkleTree::new_with_hasher(&leaves)?
         }
+    };
+    if args.positional {
+        Ok(tree.with_pair_hashing(PairHashing::Positional))
+    } else {
+        Ok(tree)
+    }
+}
+
+/// Fails when a flag describing how to build the tree contradicts a dump, which fixes its own encoding,
+/// so the tree isn't taken for the one the flags describe.
+fn check_dump_args<H>(args: &TreeArgs, tree: &MerkleTree<H>) -> Result<(), MerkleError> {
+    let contradiction = if !args.columns.is_empty() {
+        Some(String::from("--columns doesn't apply to a tree dump"))
+    } else if !args.types.is_empty()
+        && args
+            .types
+            .iter()
+            .map(|ty| ty.trim())
+            .ne(tree.leaf_encoding.iter().map(String::as_str))
+    {
+        Some(format!(
+            "--types {} doesn't match the leaf encoding of the dump, [{}]",
+            args.types.join(","),
+            tree.leaf_encoding.join(",")
+        ))
+    } else if args.standard && tree.layout != TreeLayout::Standard {
+        Some(String::from(
+            "--standard doesn't match the dump, which isn't a StandardMerkleTree",
+        ))
+    } else if args.positional && tree.pair_hashing != PairHashing::Positional {
+        Some(String::from(
+            "--positional doesn't match the dump, which sorts pairs",
+        ))
+    } else {
+        None
+    };
+    match contradiction {
+        Some(reason) => Err(MerkleError::InvalidInput(reason)),
+        None => Ok(()),
+    }
+}
+
+/// Parses the side of a proof sibling given to `verify --sides`.
+fn parse_side(side: &str) -> Result<Side, String> {
+    match side.trim().to_lowercase().as_str() {
+        "left" | "l" => Ok(Side::Left),
+        "right" | "r" => Ok(Side::Right),
+        _ => Err(format!("expected `left` or `right`, got `{}`", side)),
+    }
+}
+
+fn side_name(side: &Side) -> String {
+    match side {
+        Side::Left => String::from("left"),
+        Side::Right => String::from("right"),
     }
-    MerkleTree::new(&hex_strings)
 }
 
 /// Returns the root of the specified `tree` argument.
@@ -119,7 +842,7 @@ fn create_tree(path: &PathBuf) -> Result<MerkleTree, Box<dyn Error>> {
 ///
 /// # Returns
 ///
-/// * `Result<String, Box<dyn Error>>` - A `Result` containing the root value of the `tree` if successful,
+/// * `Result<Hash32, MerkleError>` - A `Result` containing the root value of the `tree` if successful,
 ///   otherwise returns an error.
 ///
 /// # Errors
@@ -133,51 +856,54 @@ fn create_tree(path: &PathBuf) -> Result<MerkleTree, Box<dyn Error>> {
 /// let root_value = get_root(&tree)?;
 /// ```
 ///
-fn get_root(tree: &MerkleTree) -> Result<String, Box<dyn Error>> {
+fn get_root<H>(tree: &MerkleTree<H>) -> Result<Hash32, MerkleError> {
     match &tree.root {
-        Some(root) => Ok(root.data.clone()),
-        None => Err(Box::from("No root found")),
+        Some(root) => Ok(root.data),
+        None => Err(MerkleError::EmptyInput),
     }
 }
 
-/// Finds and returns the root node from a given directed graph.
-///
-///
-/// # Arguments
-///
-/// * `graph` - A reference to the directed graph (`DiGraph`) where the root node search will be conducted.
-///
-/// # Returns
-///
-/// * `NodeIndex` - The index of the identified root node within the `graph`.
-///
-/// # Panics
-///
-/// When there's no node within the `graph` that qualifies as the root node (i.e., no node with zero incoming edges).
-///
-/// # Example
-///
-/// ```ignore
-/// let graph: DiGraph<String, ()> = tree.graph;
-/// let root_node_index = find_root_node(&my_graph);
-/// ```
-///
-fn find_root_node(graph: &DiGraph<String, ()>) -> NodeIndex {
-    for node in graph.node_indices() {
-        if graph.neighbors_directed(node, Incoming).count() == 0 {
-            return node;
+/// Returns the tree below `node` as nested `{"hash", "children"}` objects, in the order `print_tree` prints them.
+///
+/// Nodes of the highlighted proof also hold their `"role"`, like `"proof[0]"`. With `--positions` every node
+/// holds its `"level"` and `"index"`, and with `--values` every leaf holds its `"value"`. A node whose children
+/// are cut by `--max-depth` holds the number of `"leavesBelow"` it instead.
+fn tree_json<H: MerkleHasher>(view: &TreeView<H>, node: usize, depth: usize) -> Value {
+    let children: Vec<Value> = view
+        .children(node, depth)
+        .into_iter()
+        .map(|child| tree_json(view, child, depth + 1))
+        .collect();
+    let mut value = json!({ "hash": view.hash(node), "children": children });
+    if let Some(role) = view.highlight.role(node) {
+        value["role"] = json!(role.label());
+    }
+    if view.args.positions {
+        value["level"] = json!(view.level + depth);
+        value["index"] = json!(node);
+    }
+    if view.args.values {
+        if let Some(leaf_value) = view.values.get(&node) {
+            value["value"] = json!(leaf_value);
         }
     }
-    panic!("No root node found!");
+    if children.is_empty() && !view.tree.children(node).is_empty() {
+        value["leavesBelow"] = json!(view.leaves_below(node));
+    }
+    value
 }
 
-/// Prints the structure of the tree represented by a directed graph in a visual manner.
+/// Prints the structure of the tree in a visual manner, left child first.
 ///
+/// Nodes of the highlighted proof are colored and followed by their role, like `proof[0]`, so the proof
+/// stays readable with `--no-color`. A node whose children are cut by `--max-depth` is followed by the
+/// number of leaves below it.
 ///
 /// # Arguments
 ///
-/// * `graph` - A reference to the directed graph (`DiGraph`) representing the tree.
-/// * `root` - The starting point (`NodeIndex`) from which the tree printing begins. Typically, this is the root node.
+/// * `view` - The tree to print and its options.
+/// * `node` - The graph index from which the tree printing begins. Typically, this is `view.start`.
+/// * `depth` - The number of levels between `node` and `view.start`.
 /// * `prefix` - A `String` that serves as the prefix for printing and visually indicates the depth of the current node in the tree.
 ///
 /// # Usage
@@ -188,20 +914,53 @@ fn find_root_node(graph: &DiGraph<String, ()>) -> NodeIndex {
 /// # Example
 ///
 /// ```ignore
-/// let graph: DiGraph<String, ()> = tree.graph;
-/// let root_node_index = find_root_node(&my_graph);
-/// print_tree(&graph, root_node_index, "".to_string());
+/// let view = TreeView::new(&tree, &ProofHighlight::default(), &args)?;
+/// print_tree(&view, view.start, 0, "".to_string());
 /// ```
 ///
-fn print_tree(graph: &DiGraph<String, ()>, root: NodeIndex, prefix: String) {
-    let children: Vec<_> = graph.neighbors_directed(root, Outgoing).collect();
-    println!("{}", graph[root].bright_green());
+fn print_tree<H: MerkleHasher>(view: &TreeView<H>, node: usize, depth: usize, prefix: String) {
+    let mut line = match view.highlight.role(node) {
+        Some(role) => {
+            let hash = match role {
+                ProofRole::Leaf => view.hash(node).bright_yellow().bold(),
+                ProofRole::Sibling(_) => view.hash(node).bright_magenta().bold(),
+                ProofRole::Path(_) => view.hash(node).bright_red().bold(),
+            };
+            format!("{}  {}", hash, format!("← {}", role.label()).bright_white())
+        }
+        None => view.hash(node).bright_green().to_string(),
+    };
+    if view.args.positions {
+        line += &format!("  (level {}, index {})", view.level + depth, node)
+            .bright_black()
+            .to_string();
+    }
+    if view.args.values {
+        if let Some(value) = view.values.get(&node) {
+            line += &format!("  {}", value).bright_cyan().to_string();
+        }
+    }
+    println!("{}", line);
 
+    let children = view.children(node, depth);
+    if children.is_empty() && !view.tree.children(node).is_empty() {
+        let leaves = view.leaves_below(node);
+        println!(
+            "{}{}",
+            prefix.bright_blue(),
+            format!(
+                "└─ … {} {} below",
+                leaves,
+                if leaves == 1 { "leaf" } else { "leaves" }
+            )
+            .bright_black()
+        );
+    }
     for (i, child) in children.iter().enumerate() {
         let is_last = i == children.len() - 1;
         let next_prefix = if is_last { "   " } else { "│  " };
         let branch = if is_last { "└─ " } else { "├─ " };
         print!("{}{}", prefix.bright_blue(), branch.bright_blue());
-        print_tree(graph, *child, prefix.to_string() + next_prefix);
+        print_tree(view, *child, depth + 1, prefix.to_string() + next_prefix);
     }
 }
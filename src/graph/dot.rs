@@ -0,0 +1,145 @@
+//! # DOT
+//!
+//! Provides the [DOT](https://graphviz.org/doc/info/lang.html) description of a Merkle Tree, to be rendered
+//! by Graphviz or any other DOT viewer.
+//!
+//! Nodes are named after their graph index and labeled with their hash. Children are listed left child first,
+//! and `ordering=out` keeps them in that order once laid out. A [`ProofHighlight`] colors the proof of
+//! a leaf and captions each of its nodes with its role.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::graph::dot::to_dot;
+//! use eth_merkle_tree::tree::MerkleTree;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef")];
+//! let tree = MerkleTree::new(&data).expect("Tree creation error.");
+//! assert!(to_dot(&tree).contains("2 -> 0;"));
+//! ```
+
+use crate::graph::highlight::{ProofHighlight, LEAF_COLOR, PATH_EDGE_COLOR};
+use crate::tree::MerkleTree;
+use crate::utils::errors::MerkleError;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Returns the DOT description of the tree.
+pub fn to_dot<H>(tree: &MerkleTree<H>) -> String {
+    to_dot_highlighted(tree, &ProofHighlight::default())
+}
+
+/// Returns the DOT description of the tree, with the proof marked by `highlight`.
+pub fn to_dot_highlighted<H>(tree: &MerkleTree<H>, highlight: &ProofHighlight) -> String {
+    let mut dot = Vec::new();
+    write_dot_highlighted(tree, highlight, &mut dot).expect("Writing to a Vec never fails");
+    String::from_utf8(dot).expect("The DOT description is valid UTF-8")
+}
+
+/// Writes the DOT description of the tree to `writer`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_dot<H, W: Write>(tree: &MerkleTree<H>, writer: W) -> Result<(), MerkleError> {
+    write_dot_highlighted(tree, &ProofHighlight::default(), writer)
+}
+
+/// Writes the DOT description of the tree to `writer`, with the proof marked by `highlight`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_dot_highlighted<H, W: Write>(
+    tree: &MerkleTree<H>,
+    highlight: &ProofHighlight,
+    mut writer: W,
+) -> Result<(), MerkleError> {
+    writeln!(writer, "digraph merkle_tree {{")?;
+    writeln!(writer, "    graph [ordering=out];")?;
+    writeln!(writer, "    node [shape=box, fontname=\"monospace\"];")?;
+    for node in tree.graph.node_indices() {
+        let index = node.index();
+        let role = highlight.role(index);
+        let caption = role
+            .map(|role| format!("\\n{}", role.label()))
+            .unwrap_or_default();
+        let fill = match role {
+            Some(role) => Some(role.color()),
+            None if tree.children(index).is_empty() => Some(LEAF_COLOR),
+            None => None,
+        };
+        let style = fill
+            .map(|color| format!(", style=filled, fillcolor=\"{}\"", color))
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "    {} [label=\"{}{}\"{}];",
+            index, tree.graph[node], caption, style
+        )?;
+    }
+    for node in tree.graph.node_indices() {
+        for child in tree.children(node.index()) {
+            let style = if highlight.is_path_edge(node.index(), child) {
+                format!(" [color=\"{}\", penwidth=2]", PATH_EDGE_COLOR)
+            } else {
+                String::new()
+            };
+            writeln!(writer, "    {} -> {}{};", node.index(), child, style)?;
+        }
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// Writes the DOT description of the tree to the file at `path`, replacing it if it exists.
+///
+/// # Errors
+///
+/// - When the file can't be created or written.
+pub fn save_dot<H>(tree: &MerkleTree<H>, path: impl AsRef<Path>) -> Result<(), MerkleError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_dot(tree, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_values;
+    use petgraph::graph::NodeIndex;
+
+    #[test]
+    fn test_to_dot() {
+        let data = test_values(3);
+        let tree = MerkleTree::new(&data).unwrap();
+        let dot = to_dot(&tree);
+        assert!(dot.starts_with("digraph merkle_tree {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains(&format!("    5 [label=\"{}\"];", tree.root.unwrap().data)));
+        // The odd leaf is promoted: node 4 has a single child.
+        for edge in ["5 -> 3;", "5 -> 4;", "3 -> 0;", "3 -> 1;", "4 -> 2;"] {
+            assert!(dot.contains(edge), "missing {}", edge);
+        }
+        assert_eq!(dot.matches("fillcolor").count(), 3);
+        assert_eq!(dot.matches("->").count(), 5);
+    }
+
+    #[test]
+    fn test_to_dot_highlighted() {
+        let data = test_values(3);
+        let tree = MerkleTree::new(&data).unwrap();
+        let highlight = ProofHighlight::new(&tree, 2).unwrap();
+        let dot = to_dot_highlighted(&tree, &highlight);
+        assert!(dot.contains(&format!(
+            "    2 [label=\"{}\\nleaf\"",
+            tree.graph[NodeIndex::new(2)]
+        )));
+        assert!(dot.contains("\\nproof[0]\", style=filled, fillcolor=\"#d5b8f0\"]"));
+        assert!(dot.contains("5 -> 4 [color=\"#c0392b\", penwidth=2];"));
+        assert!(dot.contains("4 -> 2 [color=\"#c0392b\", penwidth=2];"));
+        assert!(dot.contains("5 -> 3;"));
+        assert_eq!(dot.matches("penwidth").count(), 2);
+    }
+}
@@ -0,0 +1,175 @@
+//! # Highlight
+//!
+//! Marks the nodes making up the proof of a leaf, so visualizations can show how the leaf leads to the root.
+//!
+//! Every node of the proof gets a [`ProofRole`]: the leaf itself, the siblings hashed along the way, which
+//! are the proof elements, and the nodes computed from the leaf up to the root.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::graph::highlight::{ProofHighlight, ProofRole};
+//! use eth_merkle_tree::tree::MerkleTree;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef"), String::from("0x123")];
+//! let tree = MerkleTree::new(&data).expect("Tree creation error.");
+//! let highlight = ProofHighlight::new(&tree, 0).expect("Not a leaf.");
+//! assert_eq!(highlight.role(1), Some(ProofRole::Sibling(0)));
+//! assert_eq!(highlight.role(tree.root_index()), Some(ProofRole::Path(2)));
+//! ```
+
+use crate::tree::MerkleTree;
+use crate::utils::errors::MerkleError;
+use std::collections::HashMap;
+
+/// The fill color of a leaf outside of the highlighted proof.
+pub(crate) const LEAF_COLOR: &str = "#e8f4ea";
+
+/// The color of the edges from the highlighted leaf to the root.
+pub(crate) const PATH_EDGE_COLOR: &str = "#c0392b";
+
+/// The part a node plays in the proof of a leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofRole {
+    /// The leaf being proven.
+    Leaf,
+    /// The sibling hashed at step `i`, `proof[i]` of `MerkleTree::generate_proof`.
+    Sibling(usize),
+    /// A node computed from the leaf once `i` siblings were hashed, `path[i]` of `MerkleTree::proof_path`.
+    /// The root is the last one.
+    Path(usize),
+}
+
+impl ProofRole {
+    /// Returns a short caption for the role, like `proof[0]`.
+    pub fn label(&self) -> String {
+        match self {
+            ProofRole::Leaf => String::from("leaf"),
+            ProofRole::Sibling(step) => format!("proof[{}]", step),
+            ProofRole::Path(step) => format!("path[{}]", step),
+        }
+    }
+
+    /// Returns the fill color of nodes with this role, as an RGB hex color.
+    pub fn color(&self) -> &'static str {
+        match self {
+            ProofRole::Leaf => "#ffd966",
+            ProofRole::Sibling(_) => "#d5b8f0",
+            ProofRole::Path(_) => "#f5b7b1",
+        }
+    }
+
+    /// Returns `true` for the leaf and the nodes computed from it, the nodes linked by the path to the root.
+    pub fn is_on_path(&self) -> bool {
+        !matches!(self, ProofRole::Sibling(_))
+    }
+}
+
+/// The role of every node in the proof of a leaf, by graph index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProofHighlight {
+    roles: HashMap<usize, ProofRole>,
+}
+
+impl ProofHighlight {
+    /// Marks the proof of the leaf at graph index `leaf_index`.
+    ///
+    /// A node promoted from an odd level has no sibling. It's on the path with the same step as its child,
+    /// since it holds the same hash.
+    ///
+    /// # Errors
+    ///
+    /// - When the index is out of bounds or doesn't point to a leaf.
+    pub fn new<H>(tree: &MerkleTree<H>, leaf_index: usize) -> Result<Self, MerkleError> {
+        if leaf_index >= tree.graph.node_count() || !tree.children(leaf_index).is_empty() {
+            return Err(MerkleError::InvalidIndex(leaf_index));
+        }
+        let mut roles = HashMap::from([(leaf_index, ProofRole::Leaf)]);
+        let mut current = leaf_index;
+        let mut step = 0;
+        while let Some(parent) = tree.parent(current) {
+            for child in tree.children(parent) {
+                if child != current {
+                    roles.insert(child, ProofRole::Sibling(step));
+                    step += 1;
+                }
+            }
+            roles.insert(parent, ProofRole::Path(step));
+            current = parent;
+        }
+        Ok(ProofHighlight { roles })
+    }
+
+    /// Returns the role of the node at graph index `index`, `None` if it isn't part of the proof.
+    pub fn role(&self, index: usize) -> Option<ProofRole> {
+        self.roles.get(&index).copied()
+    }
+
+    /// Returns `true` if the edge from `parent` to `child` is on the path from the leaf to the root.
+    pub fn is_path_edge(&self, parent: usize, child: usize) -> bool {
+        [parent, child]
+            .iter()
+            .all(|node| self.role(*node).is_some_and(|role| role.is_on_path()))
+    }
+
+    /// Returns `true` if no node is highlighted.
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_values;
+    use crate::utils::hash::Hash32;
+    use std::ops::Range;
+
+    #[test]
+    fn test_roles_match_proof() {
+        for count in [1, 2, 5, 8] {
+            let data = test_values(count);
+            for tree in [
+                MerkleTree::new(&data).unwrap(),
+                MerkleTree::new_standard(&data).unwrap(),
+            ] {
+                for value in 0..data.len() {
+                    let leaf = tree.tree_index(value).unwrap();
+                    let highlight = ProofHighlight::new(&tree, leaf).unwrap();
+                    let proof = tree.generate_proof(leaf).unwrap();
+                    let path = tree.proof_path(leaf).unwrap();
+                    let role_hashes = |role: fn(usize) -> ProofRole, steps: Range<usize>| {
+                        steps
+                            .map(|step| {
+                                tree.graph
+                                    .node_indices()
+                                    .find(|node| highlight.role(node.index()) == Some(role(step)))
+                                    .map(|node| tree.graph[node])
+                            })
+                            .collect::<Option<Vec<Hash32>>>()
+                    };
+                    assert_eq!(highlight.role(leaf), Some(ProofRole::Leaf));
+                    assert_eq!(role_hashes(ProofRole::Sibling, 0..proof.len()), Some(proof));
+                    let computed = role_hashes(ProofRole::Path, 1..path.len());
+                    assert_eq!(computed.as_deref(), Some(&path[1..]));
+                    if count > 1 {
+                        assert_eq!(
+                            highlight.role(tree.root_index()),
+                            Some(ProofRole::Path(path.len() - 1))
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_leaf() {
+        let data = vec![String::from("0xabc"), String::from("0xdef")];
+        let tree = MerkleTree::new(&data).unwrap();
+        assert!(ProofHighlight::new(&tree, tree.root_index()).is_err());
+        assert!(ProofHighlight::new(&tree, 3).is_err());
+        let highlight = ProofHighlight::new(&tree, 1).unwrap();
+        assert!(highlight.is_path_edge(2, 1));
+        assert!(!highlight.is_path_edge(2, 0));
+    }
+}
@@ -0,0 +1,253 @@
+//! # HTML
+//!
+//! Renders a Merkle Tree to a self-contained HTML page, with no script or stylesheet to fetch, to publish or
+//! embed the tree of a distribution.
+//!
+//! Every subtree is a `<details>` element that folds and unfolds on click, and the first levels start unfolded.
+//! Nodes are labeled with a shortened hash, and hovering one shows its full hash and, for a leaf, the value it
+//! was built from. A [`ProofHighlight`] colors the proof of a leaf, unfolds the subtrees leading to it and
+//! captions each of its nodes with its role.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::graph::html::to_html;
+//! use eth_merkle_tree::tree::MerkleTree;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef")];
+//! let tree = MerkleTree::new(&data).expect("Tree creation error.");
+//! assert!(to_html(&tree).starts_with("<!DOCTYPE html>"));
+//! ```
+
+use crate::graph::highlight::{ProofHighlight, LEAF_COLOR};
+use crate::tree::MerkleTree;
+use crate::utils::errors::MerkleError;
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// The number of levels below the root unfolded when the page opens.
+const OPEN_LEVELS: usize = 3;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+code, .node { font-family: monospace; }
+ul.tree, ul.tree ul { list-style: none; margin: 0; padding-left: 1.5em; }
+ul.tree { padding-left: 0; }
+ul.tree li { margin: 2px 0; }
+summary { cursor: pointer; }
+.node { padding: 1px 6px; border: 1px solid #333333; border-radius: 4px; background: #ffffff; }
+.leaf { background: LEAF_COLOR; }
+.caption { margin-left: 0.5em; color: #555555; font-size: 0.9em; }
+";
+
+const SCRIPT: &str = "\
+function unfold(open) {
+  document.querySelectorAll('ul.tree details').forEach(function (details) { details.open = open; });
+}
+";
+
+/// Returns the HTML page of the tree.
+pub fn to_html<H>(tree: &MerkleTree<H>) -> String {
+    to_html_highlighted(tree, &ProofHighlight::default())
+}
+
+/// Returns the HTML page of the tree, with the proof marked by `highlight`.
+pub fn to_html_highlighted<H>(tree: &MerkleTree<H>, highlight: &ProofHighlight) -> String {
+    let mut html = Vec::new();
+    write_html_highlighted(tree, highlight, &mut html).expect("Writing to a Vec never fails");
+    String::from_utf8(html).expect("The HTML page is valid UTF-8")
+}
+
+/// Writes the HTML page of the tree to `writer`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_html<H, W: Write>(tree: &MerkleTree<H>, writer: W) -> Result<(), MerkleError> {
+    write_html_highlighted(tree, &ProofHighlight::default(), writer)
+}
+
+/// Writes the HTML page of the tree to `writer`, with the proof marked by `highlight`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_html_highlighted<H, W: Write>(
+    tree: &MerkleTree<H>,
+    highlight: &ProofHighlight,
+    mut writer: W,
+) -> Result<(), MerkleError> {
+    let root = tree
+        .root
+        .as_ref()
+        .map(|root| root.data.to_string())
+        .unwrap_or_default();
+    let values: HashMap<usize, &[String]> = tree.leaf_values().collect();
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html lang=\"en\">")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Merkle Tree {}</title>", root)?;
+    writeln!(
+        writer,
+        "<style>\n{}</style>",
+        STYLE.replace("LEAF_COLOR", LEAF_COLOR)
+    )?;
+    writeln!(writer, "<script>\n{}</script>", SCRIPT)?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Merkle Tree</h1>")?;
+    writeln!(
+        writer,
+        "<p>Root <code>{}</code>, {} leaves.</p>",
+        root,
+        values.len()
+    )?;
+    writeln!(
+        writer,
+        "<p><button onclick=\"unfold(true)\">Unfold all</button> \
+         <button onclick=\"unfold(false)\">Fold all</button></p>"
+    )?;
+    writeln!(writer, "<ul class=\"tree\">")?;
+    if tree.graph.node_count() > 0 {
+        write_node(tree, highlight, &values, tree.root_index(), 0, &mut writer)?;
+    }
+    writeln!(writer, "</ul>")?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+    Ok(())
+}
+
+/// Writes the HTML page of the tree to the file at `path`, replacing it if it exists.
+///
+/// # Errors
+///
+/// - When the file can't be created or written.
+pub fn save_html<H>(tree: &MerkleTree<H>, path: impl AsRef<Path>) -> Result<(), MerkleError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_html(tree, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the list item of `index` and, folded in a `<details>` element, the list of its children.
+fn write_node<H, W: Write>(
+    tree: &MerkleTree<H>,
+    highlight: &ProofHighlight,
+    values: &HashMap<usize, &[String]>,
+    index: usize,
+    depth: usize,
+    writer: &mut W,
+) -> Result<(), MerkleError> {
+    let hash = tree.graph[NodeIndex::new(index)];
+    let value = values.get(&index).map(|value| escape(&value.join(", ")));
+    let role = highlight.role(index);
+    let children = tree.children(index);
+
+    let mut tooltip = hash.to_string();
+    let mut captions = Vec::new();
+    if let Some(value) = &value {
+        tooltip += &format!("&#10;{}", value);
+        captions.push(value.clone());
+    }
+    if let Some(role) = role {
+        tooltip += &format!("&#10;{}", role.label());
+        captions.push(role.label());
+    }
+    let class = if children.is_empty() {
+        "node leaf"
+    } else {
+        "node"
+    };
+    let style = role
+        .map(|role| format!(" style=\"background: {}\"", role.color()))
+        .unwrap_or_default();
+    let caption = if captions.is_empty() {
+        String::new()
+    } else {
+        format!("<span class=\"caption\">{}</span>", captions.join(" · "))
+    };
+    let node = format!(
+        "<span class=\"{}\" title=\"{}\"{}>{}</span>{}",
+        class,
+        tooltip,
+        style,
+        hash.abbreviate(8),
+        caption
+    );
+
+    if children.is_empty() {
+        writeln!(writer, "<li>{}</li>", node)?;
+        return Ok(());
+    }
+    // The subtrees leading to the highlighted leaf start unfolded, whatever their depth.
+    let open = if depth < OPEN_LEVELS || role.is_some_and(|role| role.is_on_path()) {
+        " open"
+    } else {
+        ""
+    };
+    writeln!(writer, "<li><details{}><summary>{}</summary>", open, node)?;
+    writeln!(writer, "<ul>")?;
+    for child in children {
+        write_node(tree, highlight, values, child, depth + 1, writer)?;
+    }
+    writeln!(writer, "</ul>")?;
+    writeln!(writer, "</details></li>")?;
+    Ok(())
+}
+
+/// Replaces the characters HTML would read as markup with their character references.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_values;
+
+    #[test]
+    fn test_to_html() {
+        let data = test_values(5);
+        let tree = MerkleTree::new_standard(&data).unwrap();
+        let html = to_html(&tree);
+        assert!(html.ends_with("</html>\n"));
+        assert_eq!(
+            html.matches("<details").count(),
+            tree.graph.node_count() - data.len()
+        );
+        assert_eq!(html.matches("class=\"node leaf\"").count(), data.len());
+        let root = tree.root.as_ref().unwrap().data;
+        assert!(html.contains(&format!("<code>{}</code>", root)));
+        assert!(html.contains(&format!("title=\"{}\">{}</span>", root, root.abbreviate(8))));
+        let leaf = tree.graph[NodeIndex::new(tree.tree_index(4).unwrap())];
+        assert!(html.contains(&format!("title=\"{}&#10;0x05\"", leaf)));
+    }
+
+    #[test]
+    fn test_to_html_highlighted() {
+        let data = test_values(16);
+        let tree = MerkleTree::new(&data).unwrap();
+        let html = to_html(&tree);
+        // Below the first levels, subtrees start folded.
+        assert_eq!(html.matches("<details open>").count(), 1 + 2 + 4);
+        let highlight = ProofHighlight::new(&tree, 9).unwrap();
+        let html = to_html_highlighted(&tree, &highlight);
+        assert_eq!(html.matches("<details open>").count(), 1 + 2 + 4 + 1);
+        assert!(html.contains("0x0a · leaf</span>"));
+        assert!(html.contains("style=\"background: #ffd966\""));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! # Mermaid
+//!
+//! Provides a [Mermaid](https://mermaid.js.org/syntax/flowchart.html) `graph TD` block of a Merkle Tree,
+//! rendered by GitHub, GitLab and most Markdown viewers inside a ` ```mermaid ` fence.
+//!
+//! Nodes are named `n` followed by their graph index and labeled with a shortened hash, leaves also with
+//! the value they were built from. A [`ProofHighlight`] colors the proof of a leaf and captions each of its
+//! nodes with its role.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::graph::mermaid::to_mermaid;
+//! use eth_merkle_tree::tree::MerkleTree;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef")];
+//! let tree = MerkleTree::new(&data).expect("Tree creation error.");
+//! assert!(to_mermaid(&tree).contains("n2 --> n0"));
+//! ```
+
+use crate::graph::highlight::{ProofHighlight, LEAF_COLOR, PATH_EDGE_COLOR};
+use crate::tree::MerkleTree;
+use crate::utils::errors::MerkleError;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Returns the Mermaid `graph TD` block of the tree.
+pub fn to_mermaid<H>(tree: &MerkleTree<H>) -> String {
+    to_mermaid_highlighted(tree, &ProofHighlight::default())
+}
+
+/// Returns the Mermaid `graph TD` block of the tree, with the proof marked by `highlight`.
+pub fn to_mermaid_highlighted<H>(tree: &MerkleTree<H>, highlight: &ProofHighlight) -> String {
+    let mut mermaid = Vec::new();
+    write_mermaid_highlighted(tree, highlight, &mut mermaid).expect("Writing to a Vec never fails");
+    String::from_utf8(mermaid).expect("The Mermaid block is valid UTF-8")
+}
+
+/// Writes the Mermaid `graph TD` block of the tree to `writer`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_mermaid<H, W: Write>(tree: &MerkleTree<H>, writer: W) -> Result<(), MerkleError> {
+    write_mermaid_highlighted(tree, &ProofHighlight::default(), writer)
+}
+
+/// Writes the Mermaid `graph TD` block of the tree to `writer`, with the proof marked by `highlight`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_mermaid_highlighted<H, W: Write>(
+    tree: &MerkleTree<H>,
+    highlight: &ProofHighlight,
+    mut writer: W,
+) -> Result<(), MerkleError> {
+    let values: HashMap<usize, &[String]> = tree.leaf_values().collect();
+    writeln!(writer, "graph TD")?;
+    writeln!(writer, "    classDef leaf fill:{}", LEAF_COLOR)?;
+    for node in tree.graph.node_indices() {
+        let index = node.index();
+        let mut label = tree.graph[node].abbreviate(8);
+        if let Some(value) = values.get(&index) {
+            label += &format!("<br/>{}", escape(&value.join(", ")));
+        }
+        let role = highlight.role(index);
+        if let Some(role) = role {
+            label += &format!("<br/>{}", role.label());
+        }
+        let class = if role.is_none() && tree.children(index).is_empty() {
+            ":::leaf"
+        } else {
+            ""
+        };
+        writeln!(writer, "    n{}[\"{}\"]{}", index, label, class)?;
+        if let Some(role) = role {
+            writeln!(writer, "    style n{} fill:{}", index, role.color())?;
+        }
+    }
+    // `linkStyle` refers to edges by the order they're declared in.
+    let mut path_edges = Vec::new();
+    let mut edge = 0;
+    for node in tree.graph.node_indices() {
+        for child in tree.children(node.index()) {
+            if highlight.is_path_edge(node.index(), child) {
+                path_edges.push(edge.to_string());
+            }
+            writeln!(writer, "    n{} --> n{}", node.index(), child)?;
+            edge += 1;
+        }
+    }
+    if !path_edges.is_empty() {
+        writeln!(
+            writer,
+            "    linkStyle {} stroke:{},stroke-width:2px",
+            path_edges.join(","),
+            PATH_EDGE_COLOR
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the Mermaid `graph TD` block of the tree to the file at `path`, replacing it if it exists.
+///
+/// # Errors
+///
+/// - When the file can't be created or written.
+pub fn save_mermaid<H>(tree: &MerkleTree<H>, path: impl AsRef<Path>) -> Result<(), MerkleError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_mermaid(tree, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Replaces the characters Mermaid would read as markup in a label with their entity codes.
+fn escape(text: &str) -> String {
+    text.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_values;
+
+    #[test]
+    fn test_to_mermaid() {
+        let data = test_values(3);
+        let tree = MerkleTree::new(&data).unwrap();
+        let mermaid = to_mermaid(&tree);
+        assert!(mermaid.starts_with("graph TD\n"));
+        let root = tree.root.unwrap().data.abbreviate(8);
+        assert!(mermaid.contains(&format!("    n5[\"{}\"]\n", root)));
+        assert!(mermaid.contains("<br/>0x03\"]:::leaf\n"));
+        for edge in [
+            "n5 --> n3",
+            "n5 --> n4",
+            "n3 --> n0",
+            "n3 --> n1",
+            "n4 --> n2",
+        ] {
+            assert!(mermaid.contains(edge), "missing {}", edge);
+        }
+        assert_eq!(mermaid.matches(":::leaf").count(), 3);
+        assert!(!mermaid.contains("linkStyle"));
+    }
+
+    #[test]
+    fn test_to_mermaid_highlighted() {
+        let data = test_values(3);
+        let tree = MerkleTree::new(&data).unwrap();
+        let highlight = ProofHighlight::new(&tree, 2).unwrap();
+        let mermaid = to_mermaid_highlighted(&tree, &highlight);
+        assert!(mermaid.contains("<br/>0x03<br/>leaf\"]\n    style n2 fill:#ffd966\n"));
+        assert!(mermaid.contains("<br/>proof[0]\"]\n    style n3 fill:#d5b8f0\n"));
+        // Edges are declared 3 -> 0, 3 -> 1, 4 -> 2, 5 -> 3, 5 -> 4.
+        assert!(mermaid.ends_with("    linkStyle 2,4 stroke:#c0392b,stroke-width:2px\n"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("say \"<hi>\" #1"),
+            "say #quot;#lt;hi#gt;#quot; #35;1"
+        );
+    }
+}
@@ -1,4 +1,9 @@
 //! # Graph
 //!
 
+pub mod dot;
+pub mod highlight;
+pub mod html;
+pub mod mermaid;
+pub mod svg;
 pub mod visualizer;
@@ -0,0 +1,280 @@
+//! # SVG
+//!
+//! Renders a Merkle Tree to SVG in pure Rust, without Graphviz.
+//!
+//! The root is drawn at the top and every level below it. Leaves are spread evenly from left to right
+//! and each parent is centered above its children. Nodes are labeled with a shortened hash, the full hash
+//! being shown as a tooltip. A [`ProofHighlight`] colors the proof of a leaf and captions each of its nodes
+//! with its role.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::graph::svg::to_svg;
+//! use eth_merkle_tree::tree::MerkleTree;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef")];
+//! let tree = MerkleTree::new(&data).expect("Tree creation error.");
+//! assert!(to_svg(&tree).starts_with("<svg"));
+//! ```
+
+use crate::graph::highlight::{ProofHighlight, LEAF_COLOR, PATH_EDGE_COLOR};
+use crate::tree::MerkleTree;
+use crate::utils::errors::MerkleError;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const NODE_WIDTH: f64 = 120.0;
+const NODE_HEIGHT: f64 = 28.0;
+const HORIZONTAL_GAP: f64 = 16.0;
+const VERTICAL_GAP: f64 = 56.0;
+const MARGIN: f64 = 20.0;
+
+/// The position of every node, by graph index.
+struct Layout {
+    /// The center of each node.
+    positions: Vec<(f64, f64)>,
+    width: f64,
+    height: f64,
+}
+
+/// Returns the SVG rendering of the tree.
+pub fn to_svg<H>(tree: &MerkleTree<H>) -> String {
+    to_svg_highlighted(tree, &ProofHighlight::default())
+}
+
+/// Returns the SVG rendering of the tree, with the proof marked by `highlight`.
+pub fn to_svg_highlighted<H>(tree: &MerkleTree<H>, highlight: &ProofHighlight) -> String {
+    let mut svg = Vec::new();
+    write_svg_highlighted(tree, highlight, &mut svg).expect("Writing to a Vec never fails");
+    String::from_utf8(svg).expect("The SVG rendering is valid UTF-8")
+}
+
+/// Writes the SVG rendering of the tree to `writer`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_svg<H, W: Write>(tree: &MerkleTree<H>, writer: W) -> Result<(), MerkleError> {
+    write_svg_highlighted(tree, &ProofHighlight::default(), writer)
+}
+
+/// Writes the SVG rendering of the tree to `writer`, with the proof marked by `highlight`.
+///
+/// # Errors
+///
+/// - When writing to `writer` fails.
+pub fn write_svg_highlighted<H, W: Write>(
+    tree: &MerkleTree<H>,
+    highlight: &ProofHighlight,
+    mut writer: W,
+) -> Result<(), MerkleError> {
+    let layout = layout(tree);
+    writeln!(
+        writer,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = layout.width,
+        h = layout.height
+    )?;
+    writeln!(
+        writer,
+        "  <g stroke=\"#555555\" stroke-width=\"1.5\" fill=\"none\">"
+    )?;
+    for node in tree.graph.node_indices() {
+        let (x, y) = layout.positions[node.index()];
+        for child in tree.children(node.index()) {
+            let (child_x, child_y) = layout.positions[child];
+            let style = if highlight.is_path_edge(node.index(), child) {
+                format!(" stroke=\"{}\" stroke-width=\"3\"", PATH_EDGE_COLOR)
+            } else {
+                String::new()
+            };
+            writeln!(
+                writer,
+                "    <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}/>",
+                x,
+                y + NODE_HEIGHT / 2.0,
+                child_x,
+                child_y - NODE_HEIGHT / 2.0,
+                style
+            )?;
+        }
+    }
+    writeln!(writer, "  </g>")?;
+    writeln!(
+        writer,
+        "  <g font-family=\"monospace\" font-size=\"12\" text-anchor=\"middle\">"
+    )?;
+    for node in tree.graph.node_indices() {
+        let (x, y) = layout.positions[node.index()];
+        let role = highlight.role(node.index());
+        let fill = match role {
+            Some(role) => role.color(),
+            None if tree.children(node.index()).is_empty() => LEAF_COLOR,
+            None => "#ffffff",
+        };
+        let hash = tree.graph[node];
+        writeln!(writer, "    <g>")?;
+        match role {
+            Some(role) => writeln!(writer, "      <title>{} ({})</title>", hash, role.label())?,
+            None => writeln!(writer, "      <title>{}</title>", hash)?,
+        }
+        writeln!(
+            writer,
+            "      <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\" stroke=\"#333333\"/>",
+            x - NODE_WIDTH / 2.0,
+            y - NODE_HEIGHT / 2.0,
+            NODE_WIDTH,
+            NODE_HEIGHT,
+            fill
+        )?;
+        writeln!(
+            writer,
+            "      <text x=\"{}\" y=\"{}\" dominant-baseline=\"central\">{}</text>",
+            x,
+            y,
+            hash.abbreviate(8)
+        )?;
+        if let Some(role) = role {
+            writeln!(
+                writer,
+                "      <text x=\"{}\" y=\"{}\" font-size=\"10\">{}</text>",
+                x,
+                y + NODE_HEIGHT / 2.0 + 12.0,
+                role.label()
+            )?;
+        }
+        writeln!(writer, "    </g>")?;
+    }
+    writeln!(writer, "  </g>")?;
+    writeln!(writer, "</svg>")?;
+    Ok(())
+}
+
+/// Writes the SVG rendering of the tree to the file at `path`, replacing it if it exists.
+///
+/// # Errors
+///
+/// - When the file can't be created or written.
+pub fn save_svg<H>(tree: &MerkleTree<H>, path: impl AsRef<Path>) -> Result<(), MerkleError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_svg(tree, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Places the leaves in slots from left to right, each parent above the middle of its children,
+/// and every node one row below its parent.
+fn layout<H>(tree: &MerkleTree<H>) -> Layout {
+    let mut positions = vec![(0.0, 0.0); tree.graph.node_count()];
+    let mut slots = 0;
+    let mut rows = 0;
+    if tree.graph.node_count() > 0 {
+        place(
+            tree,
+            tree.root_index(),
+            0,
+            &mut positions,
+            &mut slots,
+            &mut rows,
+        );
+    }
+    Layout {
+        positions,
+        width: slots as f64 * (NODE_WIDTH + HORIZONTAL_GAP) - HORIZONTAL_GAP + 2.0 * MARGIN,
+        height: rows as f64 * (NODE_HEIGHT + VERTICAL_GAP) - VERTICAL_GAP + 2.0 * MARGIN,
+    }
+}
+
+fn place<H>(
+    tree: &MerkleTree<H>,
+    index: usize,
+    depth: usize,
+    positions: &mut [(f64, f64)],
+    slots: &mut usize,
+    rows: &mut usize,
+) {
+    let children = tree.children(index);
+    let x = if children.is_empty() {
+        *slots += 1;
+        MARGIN + (*slots - 1) as f64 * (NODE_WIDTH + HORIZONTAL_GAP) + NODE_WIDTH / 2.0
+    } else {
+        for child in &children {
+            place(tree, *child, depth + 1, positions, slots, rows);
+        }
+        children
+            .iter()
+            .map(|child| positions[*child].0)
+            .sum::<f64>()
+            / children.len() as f64
+    };
+    *rows = (*rows).max(depth + 1);
+    positions[index] = (
+        x,
+        MARGIN + depth as f64 * (NODE_HEIGHT + VERTICAL_GAP) + NODE_HEIGHT / 2.0,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_values;
+    use petgraph::graph::NodeIndex;
+
+    #[test]
+    fn test_layout() {
+        let data = test_values(3);
+        let tree = MerkleTree::new(&data).unwrap();
+        let layout = layout(&tree);
+        let slot = NODE_WIDTH + HORIZONTAL_GAP;
+        let row = NODE_HEIGHT + VERTICAL_GAP;
+        // Leaves 0 and 1 sit under node 3, leaf 2 under its promoted copy 4, and the root 5 on top.
+        assert_eq!(layout.positions[1].0 - layout.positions[0].0, slot);
+        assert_eq!(
+            layout.positions[3].0,
+            (layout.positions[0].0 + layout.positions[1].0) / 2.0
+        );
+        assert_eq!(layout.positions[4].0, layout.positions[2].0);
+        assert_eq!(
+            layout.positions[5].0,
+            (layout.positions[3].0 + layout.positions[4].0) / 2.0
+        );
+        assert_eq!(layout.positions[0].1 - layout.positions[3].1, row);
+        assert_eq!(layout.positions[3].1 - layout.positions[5].1, row);
+        assert_eq!(layout.width, 3.0 * slot - HORIZONTAL_GAP + 2.0 * MARGIN);
+        assert_eq!(layout.height, 3.0 * row - VERTICAL_GAP + 2.0 * MARGIN);
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let data = test_values(5);
+        let tree = MerkleTree::new_standard(&data).unwrap();
+        let svg = to_svg(&tree);
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect").count(), tree.graph.node_count());
+        assert_eq!(svg.matches("<line").count(), tree.graph.edge_count());
+        let root = tree.root.unwrap().data;
+        assert!(svg.contains(&format!("<title>{}</title>", root)));
+        assert!(svg.contains(&root.abbreviate(8)));
+        assert!(!svg.contains(PATH_EDGE_COLOR));
+    }
+
+    #[test]
+    fn test_to_svg_highlighted() {
+        let data = test_values(5);
+        let tree = MerkleTree::new_standard(&data).unwrap();
+        let leaf = tree.tree_index(0).unwrap();
+        let highlight = ProofHighlight::new(&tree, leaf).unwrap();
+        let svg = to_svg_highlighted(&tree, &highlight);
+        let proof = tree.generate_proof(leaf).unwrap();
+        for (step, sibling) in proof.iter().enumerate() {
+            assert!(svg.contains(&format!("<title>{} (proof[{}])</title>", sibling, step)));
+        }
+        assert!(svg.contains(&format!(
+            "<title>{} (leaf)</title>",
+            tree.graph[NodeIndex::new(leaf)]
+        )));
+        // One path edge per level between the leaf and the root.
+        assert_eq!(svg.matches(PATH_EDGE_COLOR).count(), proof.len());
+    }
+}
@@ -8,17 +8,25 @@
 //!
 //! * Ensure that Graphviz is installed on the machine and the `dot` command is accessible from the command line.
 //!
-//! * The generated visual representation is saved as a PNG file in the `/output` directory.
-//!
-//! * Note: Before using the utility functions from this module, make sure Graphviz is correctly installed and configured.
+//! * Without Graphviz, [`crate::graph::dot`] still returns the DOT description of a tree and
+//!   [`crate::graph::svg`] renders it to SVG.
 
+use crate::graph::dot::write_dot;
 use crate::tree::MerkleTree;
-use petgraph::dot::{Config, Dot};
-use std::env;
+use crate::utils::errors::MerkleError;
 use std::fs;
-use std::process::Command;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 /// Generates a graphical representation of the given `MerkleTree` using Graphviz's `dot` command.
+///
+/// The PNG file is saved to `./output/merkle_tree.png`, see [`render_graphviz`] to choose the format and path.
+///
+/// # Returns
+///
+/// The path of the PNG file.
+///
 /// # Prerequisites
 ///
 /// This function relies on the external [Graphviz](https://www.graphviz.org/download/) software. Ensure that the `dot` command is
@@ -26,36 +34,64 @@ use std::process::Command;
 ///
 /// # Errors
 ///
-/// - When writing to the intermediate `.dot` file fails.
 /// - When creating the output directory fails.
-/// - When converting the output path to a string encounters issues.
-/// - When the `dot` command execution fails.
-///
-pub fn graphviz(tree: &MerkleTree) -> std::io::Result<()> {
-    let graph = &tree.graph;
-    let dot = format!("{:?}", Dot::with_config(&graph, &[Config::EdgeNoLabel]));
-    let dot_file = "temp.dot";
-    fs::write(dot_file, dot).expect("Unable to write to file");
-    let output_directory = "./output";
-    if fs::metadata(output_directory).is_err() {
-        fs::create_dir_all(output_directory).expect("Failed to create directory");
-    }
-    let current_dir = env::current_dir()?;
-    let output_path = current_dir.join("output/merkle_tree.png");
-    let output = output_path
-        .to_str()
-        .ok_or_else(|| std::io::Error::other("Failed to convert path to string"))?;
-
-    let status = Command::new("dot")
-        .args(["-Tpng", dot_file, "-o", output])
-        .status()
-        .expect("Failed to execute command");
+/// - When the `dot` command can't be found or its execution fails.
+///
+pub fn graphviz<H>(tree: &MerkleTree<H>) -> Result<PathBuf, MerkleError> {
+    let path = Path::new("./output").join("merkle_tree.png");
+    fs::create_dir_all("./output")?;
+    render_graphviz(tree, "png", &path)?;
+    Ok(path)
+}
 
-    if status.success() {
-        println!("PNG file saved to /output/merkle_tree.png");
-    } else {
-        return Err(std::io::Error::other("Command failed"));
+/// Renders the tree with Graphviz's `dot` command to the file at `path`.
+///
+/// The DOT description is piped to `dot`, no intermediate file is written. When `dot` fails, the error
+/// holds its exit status and what it printed to stderr.
+///
+/// # Arguments
+///
+/// * `format` - Any output format of `dot`, like `png`, `svg` or `pdf`.
+/// * `path` - The file to write, replaced if it exists.
+///
+/// # Errors
+///
+/// - When the `dot` command can't be found or its execution fails.
+///
+pub fn render_graphviz<H>(
+    tree: &MerkleTree<H>,
+    format: &str,
+    path: impl AsRef<Path>,
+) -> Result<(), MerkleError> {
+    let mut child = Command::new("dot")
+        .arg(format!("-T{}", format))
+        .arg("-o")
+        .arg(path.as_ref())
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => io::Error::new(
+                e.kind(),
+                "Graphviz's `dot` command wasn't found, install Graphviz or render the tree with graph::svg",
+            ),
+            _ => e,
+        })?;
+    // Dropping stdin closes the pipe, so `dot` stops waiting for input. A write error is only reported once
+    // `dot` exited successfully: when it fails early, the broken pipe says less than its stderr.
+    let written = child.stdin.take().map_or(Ok(()), |stdin| {
+        let mut stdin = io::BufWriter::new(stdin);
+        write_dot(tree, &mut stdin)?;
+        stdin.flush().map_err(MerkleError::from)
+    });
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "Graphviz's `dot` command failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
     }
-    fs::remove_file(dot_file).expect("Failed to remove temporary dot file");
-    Ok(())
+    written
 }
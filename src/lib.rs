@@ -1,12 +1,5 @@
 pub mod tree;
 
-pub mod graph {
-    pub mod visualizer;
-}
+pub mod graph;
 
-pub mod utils {
-    pub mod bytes;
-    pub mod errors;
-    pub mod keccak;
-    pub mod verify;
-}
+pub mod utils;
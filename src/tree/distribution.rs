@@ -0,0 +1,333 @@
+//! # Distribution
+//!
+//! Provides the claims file of a token distribution, in the shape used by Uniswap's `merkle-distributor`:
+//!
+//! ```json
+//! {
+//!   "merkleRoot": "0x...",
+//!   "tokenTotal": "0x...",
+//!   "claims": { "0xAddress": { "index": 0, "amount": "0x...", "proof": ["0x..."] } }
+//! }
+//! ```
+//!
+//! Every leaf must hold an address and an amount: either a raw `address, amount` leaf, or a typed leaf
+//! with an `address` and a `uintN` column. When a typed leaf has several of either, the columns are given
+//! to [`MerkleTree::distribution_with_columns`]. The `index` of a claim is the position of its value in the input.
+//!
+//! Only the shape of the file is Uniswap's. The proofs are those of the tree's own leaves, which don't hold the
+//! `index`, so they can't be claimed against the `MerkleDistributor` contract, whose leaves are
+//! `keccak256(abi.encodePacked(index, account, amount))`.
+
+use super::MerkleTree;
+use crate::utils::abi::{parse_uint256, split_values, AbiType};
+use crate::utils::errors::MerkleError;
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::MerkleHasher;
+use crate::utils::keccak::keccak256_bytes;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The claims of every address of a distribution, with the root and the total amount.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    pub merkle_root: Hash32,
+    /// The sum of all amounts, as `0x`-prefixed hex.
+    pub token_total: String,
+    /// The claim of each address, keyed by its EIP-55 checksummed address.
+    pub claims: BTreeMap<String, Claim>,
+}
+
+/// The claim of a single address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Claim {
+    pub index: usize,
+    /// The amount, as `0x`-prefixed hex.
+    pub amount: String,
+    pub proof: Vec<Hash32>,
+}
+
+impl Distribution {
+    /// Serializes the distribution to pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// - When `serde_json` fails to serialize the distribution.
+    pub fn to_json(&self) -> Result<String, MerkleError> {
+        serde_json::to_string_pretty(self).map_err(|e| MerkleError::InvalidDump(e.to_string()))
+    }
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Returns the claims file of the tree, with the proof of every address from [`MerkleTree::all_proofs`].
+    ///
+    /// The address and amount of a typed leaf are its single `address` and `uintN` columns.
+    ///
+    /// # Errors
+    ///
+    /// - When a typed leaf has several `address` or `uintN` columns, see [`MerkleTree::distribution_with_columns`].
+    /// - When a value doesn't hold an address and an amount, reported with the index of the value.
+    /// - When an address appears more than once, reported with the index of its second value.
+    /// - When the total amount doesn't fit in a `uint256`.
+    pub fn distribution(&self) -> Result<Distribution, MerkleError> {
+        let address = self.single_column("address", |ty| *ty == AbiType::Address)?;
+        let amount = self.single_column("amount", |ty| matches!(ty, AbiType::Uint(_)))?;
+        self.claims(address.zip(amount))
+    }
+
+    /// Returns the claims file of a typed tree like [`MerkleTree::distribution`], reading the address and
+    /// the amount from the given columns of each leaf.
+    ///
+    /// # Errors
+    ///
+    /// - When the `address` column isn't an `address`, or the `amount` column isn't a `uintN`.
+    /// - Like [`MerkleTree::distribution`] otherwise.
+    pub fn distribution_with_columns(
+        &self,
+        address: usize,
+        amount: usize,
+    ) -> Result<Distribution, MerkleError> {
+        let column_type = |column: usize| {
+            self.leaf_encoding
+                .get(column)
+                .and_then(|ty| ty.parse::<AbiType>().ok())
+        };
+        if column_type(address) != Some(AbiType::Address) {
+            return Err(MerkleError::InvalidInput(format!(
+                "column {} isn't an address",
+                address
+            )));
+        }
+        if !matches!(column_type(amount), Some(AbiType::Uint(_))) {
+            return Err(MerkleError::InvalidInput(format!(
+                "column {} isn't a uint amount",
+                amount
+            )));
+        }
+        self.claims(Some((address, amount)))
+    }
+
+    /// Returns the only column of the leaf encoding whose type matches, `None` if there is none.
+    fn single_column(
+        &self,
+        name: &str,
+        matches: impl Fn(&AbiType) -> bool,
+    ) -> Result<Option<usize>, MerkleError> {
+        let candidates: Vec<usize> = self
+            .leaf_encoding
+            .iter()
+            .enumerate()
+            .filter(|(_, ty)| ty.parse::<AbiType>().is_ok_and(|ty| matches(&ty)))
+            .map(|(column, _)| column)
+            .collect();
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [column] => Ok(Some(*column)),
+            _ => Err(MerkleError::InvalidInput(format!(
+                "columns {:?} could all hold the {}, choose the columns explicitly",
+                candidates, name
+            ))),
+        }
+    }
+
+    fn claims(&self, columns: Option<(usize, usize)>) -> Result<Distribution, MerkleError> {
+        let mut token_total = [0u8; 32];
+        let mut claims = BTreeMap::new();
+
+        for (index, (value, proof)) in self.values.iter().zip(self.all_proofs()).enumerate() {
+            let invalid = |reason: &str| MerkleError::LeafEncoding(index, reason.to_string());
+            let (address, amount) = match (columns, value.as_slice()) {
+                (Some((address, amount)), _) => (value[address].clone(), value[amount].clone()),
+                (None, [raw]) if self.leaf_encoding.is_empty() => {
+                    match split_values(raw)?.as_slice() {
+                        [address, amount] => (address.clone(), amount.clone()),
+                        _ => return Err(invalid("Expected an address and an amount")),
+                    }
+                }
+                _ => return Err(invalid("Expected an address and an amount")),
+            };
+            let address = checksum_address(&address).ok_or_else(|| invalid("Invalid address"))?;
+            let amount = parse_uint256(&amount).ok_or_else(|| invalid("Invalid amount"))?;
+            token_total = add_words(&token_total, &amount)
+                .ok_or_else(|| invalid("Token total overflows uint256"))?;
+
+            let claim = Claim {
+                index,
+                amount: to_hex_quantity(&amount),
+                proof,
+            };
+            if claims.insert(address.clone(), claim).is_some() {
+                return Err(invalid(&format!("Duplicate address {}", address)));
+            }
+        }
+
+        Ok(Distribution {
+            merkle_root: self
+                .root
+                .as_ref()
+                .map(|root| root.data)
+                .ok_or(MerkleError::EmptyInput)?,
+            token_total: to_hex_quantity(&token_total),
+            claims,
+        })
+    }
+}
+
+/// Returns the EIP-55 checksummed form of a `0x`-prefixed address, or `None` if it isn't 20 bytes of hex.
+fn checksum_address(address: &str) -> Option<String> {
+    let digits = address.trim().strip_prefix("0x")?.to_lowercase();
+    if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hash = keccak256_bytes(digits.as_bytes());
+    let checksummed = digits
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash.as_bytes()[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    Some(format!("0x{}", checksummed))
+}
+
+/// Adds two big-endian 256-bit words, returning `None` on overflow.
+fn add_words(a: &[u8; 32], b: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut sum = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let digit = a[i] as u16 + b[i] as u16 + carry;
+        sum[i] = digit as u8;
+        carry = digit >> 8;
+    }
+    (carry == 0).then_some(sum)
+}
+
+/// Formats a word as `0x`-prefixed hex without leading zero bytes, like ethers' `BigNumber.toHexString()`.
+fn to_hex_quantity(word: &[u8; 32]) -> String {
+    let start = word.iter().position(|&b| b != 0).unwrap_or(31);
+    format!("0x{}", hex::encode(&word[start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::verify::verify_proof;
+
+    #[test]
+    fn test_checksum_address() {
+        // Test vectors from EIP-55.
+        for address in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        ] {
+            assert_eq!(
+                checksum_address(&address.to_lowercase()).as_deref(),
+                Some(address)
+            );
+        }
+        assert_eq!(checksum_address("0x1234"), None);
+    }
+
+    #[test]
+    fn test_distribution() {
+        let data = vec![
+            String::from("0x901Ab22EdCA65188686C9742F2C88c946698bc90, 100"),
+            String::from("0x7b95d138cD923476b6e697391DD2aA01D15BAB27, 0xff"),
+            String::from("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, 1"),
+        ];
+        let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        let distribution = tree.distribution().expect("Invalid distribution");
+        let root = tree.root.as_ref().expect("No root found").data;
+        assert_eq!(distribution.merkle_root, root);
+        assert_eq!(distribution.token_total, "0x0164");
+        assert_eq!(distribution.claims.len(), 3);
+
+        let claim = &distribution.claims["0x7b95d138cD923476b6e697391DD2aA01D15BAB27"];
+        assert_eq!(claim.index, 1);
+        assert_eq!(claim.amount, "0xff");
+        let proof = claim.proof.iter().map(Hash32::to_string).collect();
+        assert!(verify_proof(proof, &root.to_string(), &data[1]).unwrap());
+    }
+
+    #[test]
+    fn test_typed_distribution() {
+        let values = vec![
+            vec![
+                String::from("5000000000000000000"),
+                String::from("0x1111111111111111111111111111111111111111"),
+            ],
+            vec![
+                String::from("2500000000000000000"),
+                String::from("0x2222222222222222222222222222222222222222"),
+            ],
+        ];
+        let tree = MerkleTree::of(&values, &["uint256", "address"]).unwrap();
+        let distribution = tree.distribution().expect("Invalid distribution");
+        assert_eq!(distribution.token_total, "0x68155a43676e0000");
+        assert_eq!(
+            distribution.claims["0x2222222222222222222222222222222222222222"].amount,
+            "0x22b1c8c1227a0000"
+        );
+    }
+
+    #[test]
+    fn test_distribution_columns() {
+        let values = vec![
+            vec![
+                String::from("[1, 2]"),
+                String::from("7"),
+                String::from("0x1111111111111111111111111111111111111111"),
+                String::from("100"),
+            ],
+            vec![
+                String::from("[]"),
+                String::from("8"),
+                String::from("0x2222222222222222222222222222222222222222"),
+                String::from("200"),
+            ],
+        ];
+        let tree = MerkleTree::of(&values, &["uint256[]", "uint8", "address", "uint256"]).unwrap();
+        // The array column isn't an amount, but the `uint8` one could be.
+        assert!(matches!(
+            tree.distribution(),
+            Err(MerkleError::InvalidInput(_))
+        ));
+        let distribution = tree
+            .distribution_with_columns(2, 3)
+            .expect("Invalid distribution");
+        assert_eq!(distribution.token_total, "0x012c");
+        assert!(tree.distribution_with_columns(2, 0).is_err());
+        assert!(tree.distribution_with_columns(3, 1).is_err());
+        assert!(tree.distribution_with_columns(2, 4).is_err());
+
+        let tree = MerkleTree::of(&values, &["uint256[]", "string", "address", "uint256"]).unwrap();
+        let distribution = tree.distribution().expect("Invalid distribution");
+        assert_eq!(distribution.token_total, "0x012c");
+    }
+
+    #[test]
+    fn test_distribution_errors() {
+        let duplicated = vec![
+            String::from("0x901Ab22EdCA65188686C9742F2C88c946698bc90, 100"),
+            String::from("0x901ab22edca65188686c9742f2c88c946698bc90, 200"),
+        ];
+        let tree = MerkleTree::new(&duplicated).unwrap();
+        assert!(matches!(
+            tree.distribution(),
+            Err(MerkleError::LeafEncoding(1, _))
+        ));
+
+        let addresses = vec![String::from("0x901Ab22EdCA65188686C9742F2C88c946698bc90")];
+        let tree = MerkleTree::new(&addresses).unwrap();
+        assert!(matches!(
+            tree.distribution(),
+            Err(MerkleError::LeafEncoding(0, _))
+        ));
+    }
+}
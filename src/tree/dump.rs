@@ -0,0 +1,410 @@
+//! # Dump
+//!
+//! Provides serialization of a Merkle Tree into the JSON layout of OpenZeppelin's `StandardMerkleTree.dump()`,
+//! and reloading it without rebuilding from the raw leaf list.
+//!
+//! Trees built with [`MerkleTree::of`] use the `standard-v1` format and can be exchanged with the JS library.
+//! Trees built from raw leaf strings use the same layout under the `raw-standard-v1` and `sequential-v1` formats.
+
+use super::{hash_leaves, MerkleTree, PairHashing, TreeLayout};
+use crate::utils::abi::{self, AbiType, Literal};
+use crate::utils::errors::{AbiError, MerkleError};
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::MerkleHasher;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const STANDARD_FORMAT: &str = "standard-v1";
+const RAW_STANDARD_FORMAT: &str = "raw-standard-v1";
+const SEQUENTIAL_FORMAT: &str = "sequential-v1";
+
+/// The serialized form of a `MerkleTree`.
+///
+/// # Example
+///
+/// ```
+/// use eth_merkle_tree::tree::{MerkleTree, TreeDump};
+///
+/// let values = vec![vec![String::from("0x1111111111111111111111111111111111111111"), String::from("100")]];
+/// let tree = MerkleTree::of(&values, &["address", "uint256"]).expect("Tree creation error.");
+/// let json = tree.dump().to_json().expect("Serialization error.");
+/// let loaded = MerkleTree::load(TreeDump::from_json(&json).expect("Invalid JSON.")).expect("Invalid dump.");
+/// assert_eq!(loaded.root.unwrap().data, tree.root.unwrap().data);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeDump {
+    pub format: String,
+    pub leaf_encoding: Vec<String>,
+    pub tree: Vec<Hash32>,
+    pub values: Vec<DumpValue>,
+    /// Left out for sorted pair hashing, so dumps of OpenZeppelin trees keep their exact shape.
+    #[serde(default, skip_serializing_if = "is_sorted")]
+    pub pair_hashing: PairHashing,
+}
+
+/// A value of a `TreeDump` with the index of its leaf in `tree`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpValue {
+    pub value: Vec<Value>,
+    pub tree_index: usize,
+}
+
+impl TreeDump {
+    /// Serializes the dump to pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// - When `serde_json` fails to serialize the dump.
+    pub fn to_json(&self) -> Result<String, MerkleError> {
+        serde_json::to_string_pretty(self).map_err(|e| MerkleError::InvalidDump(e.to_string()))
+    }
+
+    /// Parses a dump from JSON, such as the output of `JSON.stringify(tree.dump())`.
+    ///
+    /// # Errors
+    ///
+    /// - When the JSON doesn't follow the dump layout.
+    pub fn from_json(json: &str) -> Result<Self, MerkleError> {
+        serde_json::from_str(json).map_err(|e| MerkleError::InvalidDump(e.to_string()))
+    }
+}
+
+impl MerkleTree {
+    /// Rebuilds a MerkleTree from a dump and checks it against the dumped values.
+    ///
+    /// # Errors
+    ///
+    /// - When the dump has no values.
+    /// - When the format is unknown, or a value can't be read.
+    /// - When a value can't be encoded with the leaf encoding, reported with the index of the value.
+    /// - When the dumped nodes or tree indices differ from the ones computed from the values.
+    pub fn load(dump: TreeDump) -> Result<Self, MerkleError> {
+        Self::load_with_hasher(dump)
+    }
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Returns the full node array, the leaf encoding and the value of every leaf with its tree index.
+    pub fn dump(&self) -> TreeDump {
+        let format = match (self.layout, self.leaf_encoding.is_empty()) {
+            (TreeLayout::Standard, false) => STANDARD_FORMAT,
+            (TreeLayout::Standard, true) => RAW_STANDARD_FORMAT,
+            (TreeLayout::Sequential, _) => SEQUENTIAL_FORMAT,
+        };
+        let types: Vec<Option<AbiType>> = self
+            .leaf_encoding
+            .iter()
+            .map(|ty| ty.parse().ok())
+            .collect();
+        let order = self.layout_order();
+        let mut tree_indices = vec![0; order.len()];
+        for (tree_index, &node) in order.iter().enumerate() {
+            tree_indices[node] = tree_index;
+        }
+        let values = self
+            .values
+            .iter()
+            .zip(&self.value_indices)
+            .map(|(value, node)| DumpValue {
+                value: value
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| match types.get(i) {
+                        Some(Some(ty)) => literal_to_json(ty, v),
+                        _ => Value::String(v.clone()),
+                    })
+                    .collect(),
+                tree_index: tree_indices[*node],
+            })
+            .collect();
+
+        TreeDump {
+            format: format.to_string(),
+            leaf_encoding: self.leaf_encoding.clone(),
+            tree: order
+                .into_iter()
+                .map(|node| self.graph[NodeIndex::new(node)])
+                .collect(),
+            values,
+            pair_hashing: self.pair_hashing,
+        }
+    }
+
+    /// Returns the graph indices of the nodes in the order of the layout.
+    ///
+    /// A sequential tree stores its levels one after the other, from the leaves up to the root, until
+    /// leaves are pushed or removed and the new nodes go to the end of the graph. The levels are then
+    /// read again from the root down.
+    fn layout_order(&self) -> Vec<usize> {
+        let node_count = self.graph.node_count();
+        if self.layout == TreeLayout::Standard || node_count == 0 {
+            return (0..node_count).collect();
+        }
+        let mut levels = vec![vec![self.root_index()]];
+        while let Some(level) = levels
+            .last()
+            .filter(|level| !self.children(level[0]).is_empty())
+        {
+            let children = level.iter().flat_map(|&node| self.children(node)).collect();
+            levels.push(children);
+        }
+        levels.into_iter().rev().flatten().collect()
+    }
+
+    /// Rebuilds a MerkleTree like [`MerkleTree::load`], hashing with `H` instead of Keccak-256.
+    ///
+    /// # Errors
+    ///
+    /// - When the dump has no values, or doesn't match them.
+    pub fn load_with_hasher(dump: TreeDump) -> Result<Self, MerkleError> {
+        let values = dump
+            .values
+            .iter()
+            .map(|v| v.value.iter().map(abi::value_from_json).collect())
+            .collect::<Result<Vec<Vec<String>>, _>>()?;
+
+        let tree = match dump.format.as_str() {
+            STANDARD_FORMAT => Self::from_standard_leaves(
+                hash_leaves(&values, |value| {
+                    H::standard_leaf_hash_typed(&dump.leaf_encoding, value)
+                })?,
+                dump.pair_hashing,
+            ),
+            RAW_STANDARD_FORMAT => Self::from_standard_leaves(
+                hash_leaves(&values, |value| H::standard_leaf_hash(raw_value(value)?))?,
+                dump.pair_hashing,
+            ),
+            SEQUENTIAL_FORMAT => Self::from_sequential_leaves(
+                hash_leaves(&values, |value| H::leaf_hash(raw_value(value)?))?,
+                dump.pair_hashing,
+            ),
+            format => {
+                return Err(MerkleError::InvalidDump(format!(
+                    "Unknown format '{}'",
+                    format
+                )))
+            }
+        };
+
+        let nodes_match = tree
+            .graph
+            .raw_nodes()
+            .iter()
+            .map(|node| node.weight)
+            .eq(dump.tree.iter().copied());
+        if !nodes_match {
+            return Err(MerkleError::InvalidDump(String::from(
+                "Merkle tree is invalid",
+            )));
+        }
+        if let Some(index) = dump
+            .values
+            .iter()
+            .zip(&tree.value_indices)
+            .position(|(value, tree_index)| value.tree_index != *tree_index)
+        {
+            return Err(MerkleError::InvalidDump(format!(
+                "Merkle tree does not contain the expected value at index {}",
+                index
+            )));
+        }
+        Ok(tree.with_values(values, dump.leaf_encoding))
+    }
+}
+
+fn is_sorted(pair_hashing: &PairHashing) -> bool {
+    *pair_hashing == PairHashing::Sorted
+}
+
+/// Returns the single value of a leaf built from a raw string.
+fn raw_value(value: &[String]) -> Result<&str, MerkleError> {
+    match value {
+        [raw] => Ok(raw),
+        _ => Err(AbiError::LengthMismatch(1, value.len()).into()),
+    }
+}
+
+/// Converts a value string to JSON, turning arrays and tuples into JSON arrays the way the JS library expects them.
+fn literal_to_json(ty: &AbiType, value: &str) -> Value {
+    match abi::parse_value(ty, value) {
+        Ok(literal) => typed_literal_to_json(ty, &literal),
+        Err(_) => Value::String(value.to_string()),
+    }
+}
+
+fn typed_literal_to_json(ty: &AbiType, literal: &Literal) -> Value {
+    match (ty, literal) {
+        (AbiType::Array(inner) | AbiType::FixedArray(inner, _), Literal::List(items)) => {
+            Value::Array(
+                items
+                    .iter()
+                    .map(|item| typed_literal_to_json(inner, item))
+                    .collect(),
+            )
+        }
+        (AbiType::Tuple(types), Literal::List(items)) => Value::Array(
+            types
+                .iter()
+                .zip(items)
+                .map(|(ty, item)| typed_literal_to_json(ty, item))
+                .collect(),
+        ),
+        // The JS library ABI-encodes any truthy value as `true`, so `"false"` must be written as a boolean.
+        (AbiType::Bool, Literal::Scalar(s)) => match s.as_ref() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(s.to_string()),
+        },
+        (_, Literal::Scalar(s)) => Value::String(s.to_string()),
+        (_, Literal::List(items)) => Value::Array(
+            items
+                .iter()
+                .map(|item| typed_literal_to_json(ty, item))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed_tree() -> MerkleTree {
+        let values = vec![
+            vec![
+                String::from("0x1111111111111111111111111111111111111111"),
+                String::from("5000000000000000000"),
+            ],
+            vec![
+                String::from("0x2222222222222222222222222222222222222222"),
+                String::from("2500000000000000000"),
+            ],
+        ];
+        MerkleTree::of(&values, &["address", "uint256"]).expect("Failed to create Merkle Tree")
+    }
+
+    #[test]
+    fn test_dump_standard_layout() {
+        let tree = typed_tree();
+        let dump = tree.dump();
+        assert_eq!(dump.format, "standard-v1");
+        assert_eq!(dump.leaf_encoding, vec!["address", "uint256"]);
+        assert_eq!(dump.tree.len(), 3);
+        assert_eq!(dump.tree[0], tree.root.as_ref().unwrap().data);
+        for (value_index, value) in dump.values.iter().enumerate() {
+            assert_eq!(tree.tree_index(value_index), Some(value.tree_index));
+        }
+
+        let json: Value = serde_json::from_str(&dump.to_json().unwrap()).unwrap();
+        assert!(json["tree"][0].as_str().unwrap().starts_with("0x"));
+        assert!(json["values"][0]["treeIndex"].is_u64());
+        assert_eq!(
+            json["values"][0]["value"][0],
+            "0x1111111111111111111111111111111111111111"
+        );
+    }
+
+    #[test]
+    fn test_load_round_trip() {
+        let tree = typed_tree();
+        let json = tree.dump().to_json().unwrap();
+        // The JS library dumps numbers that fit in a double as JSON numbers.
+        let json = json.replace("\"5000000000000000000\"", "5000000000000000000");
+        let loaded = MerkleTree::load(TreeDump::from_json(&json).unwrap()).expect("Invalid dump");
+        assert_eq!(loaded.root.unwrap().data, tree.root.unwrap().data);
+        assert_eq!(loaded.values, tree.values);
+        assert_eq!(loaded.leaf_encoding, tree.leaf_encoding);
+    }
+
+    #[test]
+    fn test_load_raw_layouts() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        for tree in [
+            MerkleTree::new(&data).unwrap(),
+            MerkleTree::new_standard(&data).unwrap(),
+        ] {
+            let loaded = MerkleTree::load(tree.dump()).expect("Invalid dump");
+            assert_eq!(loaded.layout, tree.layout);
+            assert_eq!(loaded.dump(), tree.dump());
+            assert!(!tree.dump().to_json().unwrap().contains("pairHashing"));
+
+            let positional = tree.with_pair_hashing(PairHashing::Positional);
+            let json = positional.dump().to_json().unwrap();
+            assert!(json.contains("\"pairHashing\": \"positional\""));
+            let loaded =
+                MerkleTree::load(TreeDump::from_json(&json).unwrap()).expect("Invalid dump");
+            assert_eq!(loaded.pair_hashing, PairHashing::Positional);
+            assert_eq!(loaded.dump(), positional.dump());
+        }
+    }
+
+    #[test]
+    fn test_load_array_values() {
+        let values = vec![
+            vec![String::from("[1, 2]"), String::from("(\"a, b\", true)")],
+            vec![String::from("[3]"), String::from("(c, false)")],
+        ];
+        let tree = MerkleTree::of(&values, &["uint256[]", "(string,bool)"]).unwrap();
+        let dump = tree.dump();
+        assert_eq!(
+            dump.values[0].value,
+            vec![
+                serde_json::json!(["1", "2"]),
+                serde_json::json!(["a, b", true])
+            ]
+        );
+        assert_eq!(dump.values[1].value[1], serde_json::json!(["c", false]));
+        let loaded = MerkleTree::load(dump).expect("Invalid dump");
+        assert_eq!(loaded.root.unwrap().data, tree.root.unwrap().data);
+    }
+
+    #[test]
+    fn test_bool_values_round_trip() {
+        let values = vec![
+            vec![String::from("0x01"), String::from("false")],
+            vec![String::from("0x02"), String::from("true")],
+        ];
+        let tree = MerkleTree::of(&values, &["bytes", "bool"]).unwrap();
+        let json = tree.dump().to_json().unwrap();
+        assert!(json.contains("false") && !json.contains("\"false\""));
+        let loaded = MerkleTree::load(TreeDump::from_json(&json).unwrap()).expect("Invalid dump");
+        assert_eq!(loaded.values, values);
+        assert_eq!(loaded.dump(), tree.dump());
+        // `false` and `true` are different leaves.
+        assert_ne!(
+            tree.graph.raw_nodes()[0].weight,
+            tree.graph.raw_nodes()[1].weight
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_tampered_dump() {
+        let tree = typed_tree();
+
+        let mut dump = tree.dump();
+        dump.tree[1] = dump.tree[0];
+        assert!(matches!(
+            MerkleTree::load(dump),
+            Err(MerkleError::InvalidDump(_))
+        ));
+
+        let mut dump = tree.dump();
+        let tree_index = dump.values[0].tree_index;
+        dump.values[0].tree_index = dump.values[1].tree_index;
+        dump.values[1].tree_index = tree_index;
+        assert!(matches!(
+            MerkleTree::load(dump),
+            Err(MerkleError::InvalidDump(_))
+        ));
+
+        let mut dump = tree.dump();
+        dump.format = String::from("simple-v1");
+        assert!(MerkleTree::load(dump).is_err());
+    }
+}
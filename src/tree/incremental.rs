@@ -0,0 +1,319 @@
+//! # Incremental
+//!
+//! Provides an append-only Merkle Tree of fixed depth, like the ETH2 deposit contract or Tornado Cash's
+//! `MerkleTreeWithHistory`.
+//!
+//! Empty leaves are zero hashes, so the root is defined for any number of leaves. With the same hash function
+//! and empty leaf, [`IncrementalMerkleTree::root`] matches the root of `MerkleTreeWithHistory` after the same
+//! appends. The deposit contract mixes the number of deposits into its root, which
+//! [`IncrementalMerkleTree::deposit_root`] does too. Pairs are hashed positionally, left child first. Only the
+//! nodes on the path of each appended leaf are hashed, and the most recent roots are kept so a root
+//! seen on-chain a few appends ago can still be recognized.
+
+use super::{DirectedProof, Side};
+use crate::utils::errors::MerkleError;
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// The deepest tree supported, whose leaf count still fits in a `u64`.
+pub const MAX_DEPTH: usize = 64;
+
+/// The number of recent roots kept by default, `ROOT_HISTORY_SIZE` in `MerkleTreeWithHistory`.
+pub const DEFAULT_ROOT_HISTORY: usize = 30;
+
+/// An append-only Merkle Tree of fixed depth.
+///
+/// # Example
+///
+/// ```
+/// use eth_merkle_tree::tree::IncrementalMerkleTree;
+/// use eth_merkle_tree::utils::keccak::leaf_hash;
+///
+/// let mut tree = IncrementalMerkleTree::new(32).expect("Invalid depth.");
+/// let index = tree.append(leaf_hash("0xabc").unwrap()).expect("Tree is full.");
+/// let proof = tree.generate_proof(index).expect("Invalid index.");
+/// assert_eq!(proof.siblings.len(), 32);
+/// assert!(tree.is_known_root(&tree.root()));
+/// ```
+pub struct IncrementalMerkleTree<H = Keccak256> {
+    depth: usize,
+    /// The hash of an empty subtree at each level, from an empty leaf up to an empty tree.
+    zeros: Vec<Hash32>,
+    /// The filled nodes of each level, from the leaves up to the root. Nodes right of them are zero hashes.
+    levels: Vec<Vec<Hash32>>,
+    roots: VecDeque<Hash32>,
+    root_history: usize,
+    hasher: PhantomData<H>,
+}
+
+impl IncrementalMerkleTree {
+    /// Creates an empty tree of the given depth whose empty leaves are zero bytes, like the deposit contract.
+    ///
+    /// # Errors
+    ///
+    /// - When `depth` is zero or greater than [`MAX_DEPTH`].
+    pub fn new(depth: usize) -> Result<Self, MerkleError> {
+        Self::new_with_hasher(depth)
+    }
+}
+
+impl<H: MerkleHasher> IncrementalMerkleTree<H> {
+    /// Creates an empty tree like [`IncrementalMerkleTree::new`], hashing with `H` instead of Keccak-256.
+    ///
+    /// # Errors
+    ///
+    /// - When `depth` is zero or greater than [`MAX_DEPTH`].
+    pub fn new_with_hasher(depth: usize) -> Result<Self, MerkleError> {
+        if depth == 0 || depth > MAX_DEPTH {
+            return Err(MerkleError::InvalidInput(format!(
+                "Depth must be between 1 and {}, got {}",
+                MAX_DEPTH, depth
+            )));
+        }
+        let mut tree = IncrementalMerkleTree {
+            depth,
+            zeros: Vec::new(),
+            levels: vec![Vec::new(); depth + 1],
+            roots: VecDeque::new(),
+            root_history: DEFAULT_ROOT_HISTORY,
+            hasher: PhantomData,
+        };
+        tree.reset_zeros(Hash32::default());
+        Ok(tree)
+    }
+
+    /// Uses `zero_leaf` as the empty leaf, like the `ZERO_VALUE` of `MerkleTreeWithHistory`.
+    ///
+    /// The tree must still be empty, any appended leaf is dropped.
+    pub fn with_zero_leaf(mut self, zero_leaf: Hash32) -> Self {
+        self.levels = vec![Vec::new(); self.depth + 1];
+        self.roots.clear();
+        self.reset_zeros(zero_leaf);
+        self
+    }
+
+    /// Keeps the last `root_history` roots, at least one, instead of [`DEFAULT_ROOT_HISTORY`].
+    pub fn with_root_history(mut self, root_history: usize) -> Self {
+        self.root_history = root_history.max(1);
+        while self.roots.len() > self.root_history {
+            self.roots.pop_front();
+        }
+        self
+    }
+
+    fn reset_zeros(&mut self, zero_leaf: Hash32) {
+        self.zeros = Vec::with_capacity(self.depth + 1);
+        self.zeros.push(zero_leaf);
+        for level in 0..self.depth {
+            let zero = self.zeros[level];
+            self.zeros.push(H::hash_concat(&zero, &zero));
+        }
+        self.roots.push_back(self.zeros[self.depth]);
+    }
+
+    /// Returns the number of levels between the root and the leaves.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of appended leaves.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns `true` if no leaf was appended yet.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Returns the hash of an empty subtree of the given height, `0` being an empty leaf.
+    pub fn zero_hash(&self, height: usize) -> Option<Hash32> {
+        self.zeros.get(height).copied()
+    }
+
+    /// Appends a leaf, hashing only the nodes on its path to the root.
+    ///
+    /// # Errors
+    ///
+    /// - When the tree already holds `2^depth` leaves.
+    ///
+    /// # Returns
+    ///
+    /// - The index of the appended leaf.
+    pub fn append(&mut self, leaf: Hash32) -> Result<usize, MerkleError> {
+        let index = self.len();
+        if (index as u128) >> self.depth != 0 {
+            return Err(MerkleError::TreeFull(self.depth));
+        }
+        self.levels[0].push(leaf);
+        let mut node = leaf;
+        let mut position = index;
+        for level in 0..self.depth {
+            node = if position.is_multiple_of(2) {
+                H::hash_concat(&node, &self.zeros[level])
+            } else {
+                H::hash_concat(&self.levels[level][position - 1], &node)
+            };
+            position /= 2;
+            let parents = &mut self.levels[level + 1];
+            if position < parents.len() {
+                parents[position] = node;
+            } else {
+                parents.push(node);
+            }
+        }
+
+        if self.roots.len() == self.root_history {
+            self.roots.pop_front();
+        }
+        self.roots.push_back(node);
+        Ok(index)
+    }
+
+    /// Returns the current root, the zero hash of the full depth while the tree is empty.
+    pub fn root(&self) -> Hash32 {
+        self.levels[self.depth]
+            .first()
+            .copied()
+            .unwrap_or(self.zeros[self.depth])
+    }
+
+    /// Returns the root with the number of leaves mixed in, like `get_deposit_root` of the ETH2 deposit contract:
+    /// `hash(root ++ le64(count) ++ 24 zero bytes)`.
+    ///
+    /// It matches the deposit contract's root for a tree of depth 32 built with [`crate::utils::hasher::Sha256`].
+    pub fn deposit_root(&self) -> Hash32 {
+        let mut data = Vec::with_capacity(64);
+        data.extend_from_slice(self.root().as_bytes());
+        data.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        data.resize(64, 0);
+        H::hash(&data)
+    }
+
+    /// Returns the recent roots, from the oldest to the current one.
+    pub fn recent_roots(&self) -> impl Iterator<Item = &Hash32> {
+        self.roots.iter()
+    }
+
+    /// Returns `true` if `root` is one of the recent roots, like `isKnownRoot` in `MerkleTreeWithHistory`.
+    pub fn is_known_root(&self, root: &Hash32) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Generates a proof of inclusion for the leaf at `index` against the current root.
+    ///
+    /// The proof always has `depth` siblings, zero hashes standing for the empty subtrees.
+    /// It can be checked with [`crate::utils::verify::verify_positional_proof`].
+    ///
+    /// # Errors
+    ///
+    /// - When no leaf was appended at `index`.
+    pub fn generate_proof(&self, index: usize) -> Result<DirectedProof, MerkleError> {
+        if index >= self.len() {
+            return Err(MerkleError::InvalidIndex(index));
+        }
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut sides = Vec::with_capacity(self.depth);
+        let mut position = index;
+        for level in 0..self.depth {
+            let sibling = position ^ 1;
+            siblings.push(
+                self.levels[level]
+                    .get(sibling)
+                    .copied()
+                    .unwrap_or(self.zeros[level]),
+            );
+            sides.push(if sibling < position {
+                Side::Left
+            } else {
+                Side::Right
+            });
+            position /= 2;
+        }
+        Ok(DirectedProof { siblings, sides })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{reference_tree, test_values, PairHashing};
+    use crate::utils::hasher::Sha256;
+    use crate::utils::keccak::leaf_hash;
+    use crate::utils::verify::verify_positional_proof;
+
+    #[test]
+    fn test_zero_hashes() {
+        let tree = IncrementalMerkleTree::new(32).expect("Invalid depth");
+        // keccak256(bytes32(0), bytes32(0)), zeros(1) of `MerkleTreeWithHistory` with a zero leaf.
+        assert_eq!(
+            tree.zero_hash(1).unwrap().to_hex(),
+            "ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"
+        );
+        assert_eq!(tree.root(), tree.zero_hash(32).unwrap());
+        assert!(tree.is_empty());
+        assert!(IncrementalMerkleTree::new(0).is_err());
+        assert!(IncrementalMerkleTree::new(MAX_DEPTH + 1).is_err());
+    }
+
+    #[test]
+    fn test_deposit_root() {
+        let mut tree = IncrementalMerkleTree::<Sha256>::new_with_hasher(32).expect("Invalid depth");
+        // `get_deposit_root()` of the deposit contract before any deposit.
+        assert_eq!(
+            tree.deposit_root().to_hex(),
+            "d70a234731285c6804c2a4f56711ddb8c82c99740f207854891028af34e27e5e"
+        );
+        tree.append(Hash32::default()).unwrap();
+        // The root is unchanged by a zero leaf, but the count mixed into the deposit root isn't.
+        assert_eq!(tree.root(), tree.zero_hash(32).unwrap());
+        let mut data = tree.root().as_bytes().to_vec();
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        assert_eq!(tree.deposit_root(), Sha256::hash(&data));
+    }
+
+    #[test]
+    fn test_append_matches_full_tree() {
+        let data = test_values(8);
+        let leaves: Vec<Hash32> = data.iter().map(|d| leaf_hash(d).unwrap()).collect();
+        let mut tree = IncrementalMerkleTree::new(3).expect("Invalid depth");
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert_eq!(tree.append(*leaf).unwrap(), i);
+        }
+        let full = reference_tree(&data, PairHashing::Positional);
+        assert_eq!(tree.root(), full.root.unwrap().data);
+        assert!(matches!(
+            tree.append(leaves[0]),
+            Err(MerkleError::TreeFull(3))
+        ));
+    }
+
+    #[test]
+    fn test_proofs_and_root_history() {
+        let mut tree = IncrementalMerkleTree::new(4)
+            .expect("Invalid depth")
+            .with_root_history(3);
+        let data = test_values(5);
+        let mut roots = vec![tree.root()];
+        for value in &data {
+            tree.append(leaf_hash(value).unwrap()).unwrap();
+            roots.push(tree.root());
+            let root = tree.root().to_string();
+            for (index, value) in data[..tree.len()].iter().enumerate() {
+                let proof = tree.generate_proof(index).expect("Invalid index");
+                assert_eq!(proof.path_bits(), index as u64);
+                let siblings = proof.siblings.iter().map(Hash32::to_string).collect();
+                let leaf = leaf_hash(value).unwrap();
+                assert!(verify_positional_proof(siblings, &proof.sides, &root, &leaf).unwrap());
+            }
+        }
+        assert!(tree.generate_proof(5).is_err());
+        assert_eq!(tree.recent_roots().count(), 3);
+        assert!(tree.is_known_root(&roots[3]));
+        assert!(!tree.is_known_root(&roots[2]));
+        assert_eq!(tree.recent_roots().last(), Some(&tree.root()));
+    }
+}
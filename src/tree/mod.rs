@@ -2,22 +2,135 @@
 //!
 //! Provides the creation of a Merkle Tree, finding the location of a leaf, and generating a proof for a specific leaf.
 //!
+//! Two layouts are supported: the sequential layout built by [`MerkleTree::new`], and the
+//! OpenZeppelin `StandardMerkleTree` layout built by [`MerkleTree::new_standard`].
+//!
+//! Pairs are hashed sorted by default. [`MerkleTree::with_pair_hashing`] switches a tree to
+//! positional hashing, whose proofs carry the side of each sibling, see [`MerkleTree::generate_directed_proof`].
+//!
+//! With the `parallel` feature, leaves and the nodes of each level are hashed on all cores with rayon.
+//! The trees built are the same as without it.
+//!
 extern crate petgraph;
 
-use crate::utils::bytes::hash_pair;
-use crate::utils::keccak::keccak256;
+use crate::utils::errors::{AbiError, MerkleError};
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+
+mod distribution;
+mod dump;
+pub mod incremental;
+mod solidity;
+pub mod sparse;
+pub mod stream;
+
+pub use distribution::{Claim, Distribution};
+pub use dump::TreeDump;
+pub use incremental::IncrementalMerkleTree;
+pub use solidity::SolidityOptions;
+pub use sparse::{SparseMerkleTree, SparseProof};
+pub use stream::{SpilledTree, StreamingBuilder};
 
 /// Represents a node in the Merkle Tree.
 pub struct MerkleNode {
-    pub data: String,
+    pub data: Hash32,
+}
+
+/// Describes how the leaves of a `MerkleTree` are hashed and arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeLayout {
+    /// Leaves are hashed once and kept in input order. Each level is paired from left to right
+    /// and an odd node is promoted to the next level unchanged.
+    Sequential,
+    /// Leaves are double hashed, sorted and stored as a complete binary tree, exactly like
+    /// OpenZeppelin's `StandardMerkleTree`. Node `i` of the graph is `tree[i]` of the JS library.
+    Standard,
+}
+
+/// Describes how the two children of a node are combined into its hash.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PairHashing {
+    /// The children are sorted before hashing, like OpenZeppelin's `MerkleProof`, so proofs need no directions.
+    #[default]
+    Sorted,
+    /// The left child is always hashed first, like the ETH2 deposit contract and SSZ. Proofs need the side
+    /// of every sibling to be verified.
+    Positional,
+}
+
+/// The side of the pair a proof sibling sits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A proof of inclusion with the side of each sibling, from the leaf up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectedProof {
+    pub siblings: Vec<Hash32>,
+    /// For each sibling, the side it's hashed on.
+    pub sides: Vec<Side>,
+}
+
+impl DirectedProof {
+    /// Packs the sides into an index bitmask: bit `i` is set when `siblings[i]` is on the left,
+    /// that is when the path goes through a right child at step `i`.
+    ///
+    /// For a tree with a power-of-two number of leaves this is the index of the leaf, as used by the
+    /// deposit contract's `is_valid_merkle_branch`. Only the first 64 steps are packed.
+    pub fn path_bits(&self) -> u64 {
+        self.sides
+            .iter()
+            .take(64)
+            .enumerate()
+            .filter(|(_, side)| **side == Side::Left)
+            .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+}
+
+/// A proof of inclusion for several leaves at once, as produced by OpenZeppelin's `getMultiProof`.
+///
+/// The fields map to the arguments of `MerkleProof.multiProofVerify(proof, proofFlags, root, leaves)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    /// The leaf hashes being proven, in the order expected by the verifier.
+    pub leaves: Vec<Hash32>,
+    /// The sibling hashes that can't be computed from the leaves.
+    pub proof: Vec<Hash32>,
+    /// For each hashing step, `true` if the second operand comes from the leaves or computed hashes
+    /// and `false` if it comes from `proof`.
+    pub proof_flags: Vec<bool>,
 }
 
 /// Represents a Merkle Tree structure with its root and graph representation.
-pub struct MerkleTree {
+///
+/// The tree hashes its leaves and nodes with `H`, Keccak-256 unless another [`MerkleHasher`] is chosen.
+pub struct MerkleTree<H = Keccak256> {
     pub root: Option<MerkleNode>,
-    pub graph: DiGraph<String, ()>,
+    pub graph: DiGraph<Hash32, ()>,
+    pub layout: TreeLayout,
+    pub pair_hashing: PairHashing,
+    /// The values the leaves were built from, in input order.
+    pub values: Vec<Vec<String>>,
+    /// The Solidity types of the values, empty when each leaf was hashed from a single raw string.
+    pub leaf_encoding: Vec<String>,
+    /// The graph index of the leaf of each value.
+    value_indices: Vec<usize>,
+    /// The indices of the values of each leaf hash, in ascending order.
+    leaf_positions: HashMap<Hash32, Vec<usize>>,
+    /// The graph index of the root.
+    root_index: usize,
+    hasher: PhantomData<H>,
 }
 
 impl MerkleTree {
@@ -25,80 +138,641 @@ impl MerkleTree {
     ///
     /// # Errors
     ///
-    /// - When there's a problem hashing the data with `keccak256`.
-    /// - When the `hash_pair` function encounters issues.
-    pub fn new(data: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut graph = DiGraph::new();
-        let mut nodes: Vec<String> = data
+    /// - When `data` is empty.
+    /// - When a leaf can't be hashed with `keccak256`, reported with the index of the leaf.
+    pub fn new(data: &[String]) -> Result<Self, MerkleError> {
+        Self::new_with_hasher(data)
+    }
+
+    /// Creates a new MerkleTree compatible with OpenZeppelin's `StandardMerkleTree`.
+    ///
+    /// Every leaf is hashed with [`crate::utils::keccak::standard_leaf_hash`], the hashes are sorted and the tree is
+    /// stored as a complete binary tree, so roots and proofs match the JS library byte-for-byte
+    /// and can be checked with `MerkleProof.verify`.
+    ///
+    /// # Errors
+    ///
+    /// - When `data` is empty.
+    /// - When a leaf can't be hashed with `keccak256`, reported with the index of the leaf.
+    pub fn new_standard(data: &[String]) -> Result<Self, MerkleError> {
+        Self::new_standard_with_hasher(data)
+    }
+
+    /// Creates a new MerkleTree from typed values, like OpenZeppelin's `StandardMerkleTree.of(values, leafEncoding)`.
+    ///
+    /// Each leaf is `keccak256(bytes.concat(keccak256(abi.encode(values))))`, see [`crate::utils::abi`] for the
+    /// supported types and value syntax.
+    ///
+    /// # Errors
+    ///
+    /// - When `values` is empty.
+    /// - When a value can't be encoded with the `leaf_encoding` types, reported with the index of the value.
+    pub fn of(values: &[Vec<String>], leaf_encoding: &[&str]) -> Result<Self, MerkleError> {
+        Self::of_with_hasher(values, leaf_encoding)
+    }
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Creates a new MerkleTree like [`MerkleTree::new`], hashing with `H` instead of Keccak-256.
+    ///
+    /// # Errors
+    ///
+    /// - When `data` is empty.
+    /// - When a leaf can't be decoded, reported with the index of the leaf.
+    pub fn new_with_hasher(data: &[String]) -> Result<Self, MerkleError> {
+        let leaves = hash_leaves(data, |node| H::leaf_hash(node))?;
+        Ok(Self::from_sequential_leaves(leaves, PairHashing::Sorted)
+            .with_values(raw_values(data), Vec::new()))
+    }
+
+    /// Creates a new MerkleTree like [`MerkleTree::new_standard`], hashing with `H` instead of Keccak-256.
+    ///
+    /// # Errors
+    ///
+    /// - When `data` is empty.
+    /// - When a leaf can't be decoded, reported with the index of the leaf.
+    pub fn new_standard_with_hasher(data: &[String]) -> Result<Self, MerkleError> {
+        let leaves = hash_leaves(data, |node| H::standard_leaf_hash(node))?;
+        Ok(Self::from_standard_leaves(leaves, PairHashing::Sorted)
+            .with_values(raw_values(data), Vec::new()))
+    }
+
+    /// Creates a new MerkleTree like [`MerkleTree::of`], hashing with `H` instead of Keccak-256.
+    ///
+    /// # Errors
+    ///
+    /// - When `values` is empty.
+    /// - When a value can't be encoded with the `leaf_encoding` types, reported with the index of the value.
+    pub fn of_with_hasher(
+        values: &[Vec<String>],
+        leaf_encoding: &[&str],
+    ) -> Result<Self, MerkleError> {
+        let leaves = hash_leaves(values, |value| {
+            H::standard_leaf_hash_typed(leaf_encoding, value)
+        })?;
+        let leaf_encoding = leaf_encoding.iter().map(|t| t.to_string()).collect();
+        Ok(Self::from_standard_leaves(leaves, PairHashing::Sorted)
+            .with_values(values.to_vec(), leaf_encoding))
+    }
+
+    /// Rebuilds the tree with another way of hashing pairs, keeping its layout, leaves and values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use eth_merkle_tree::tree::{MerkleTree, PairHashing};
+    ///
+    /// let data = vec![String::from("0xabc"), String::from("0xdef")];
+    /// let tree = MerkleTree::new(&data)
+    ///     .expect("Tree creation error.")
+    ///     .with_pair_hashing(PairHashing::Positional);
+    /// ```
+    pub fn with_pair_hashing(self, pair_hashing: PairHashing) -> Self {
+        if pair_hashing == self.pair_hashing {
+            return self;
+        }
+        let leaves = self
+            .value_indices
             .iter()
-            .map(|node| keccak256(node.as_str()).expect("Keccak Error."))
+            .map(|&index| self.graph[NodeIndex::new(index)])
             .collect();
-        let mut previous_layer_indices = Vec::new();
+        let tree = match self.layout {
+            TreeLayout::Sequential => Self::from_sequential_leaves(leaves, pair_hashing),
+            TreeLayout::Standard => Self::from_standard_leaves(leaves, pair_hashing),
+        };
+        tree.with_values(self.values, self.leaf_encoding)
+    }
 
-        for node_data in &nodes {
-            let index = graph.add_node(node_data.clone());
-            previous_layer_indices.push(index);
-        }
-
-        while nodes.len() > 1 {
-            let mut new_level = Vec::new();
-            let mut new_indices = Vec::new();
-
-            for i in (0..nodes.len()).step_by(2) {
-                let hashed_data: String = if i + 1 < nodes.len() {
-                    hash_pair(nodes[i].as_str(), nodes[i + 1].as_str())?
-                } else {
-                    nodes[i].clone()
-                };
-                new_level.push(hashed_data.clone());
-                let current_index = graph.add_node(hashed_data);
-                if let Some(left_node_index) = previous_layer_indices.get(i) {
-                    graph.add_edge(current_index, *left_node_index, ());
-                }
-                if let Some(right_node_index) = previous_layer_indices.get(i + 1) {
-                    graph.add_edge(current_index, *right_node_index, ());
+    /// Hashes the children of a node, `left` being the one with the lower graph index.
+    fn hash_children(pair_hashing: PairHashing, left: &Hash32, right: &Hash32) -> Hash32 {
+        match pair_hashing {
+            PairHashing::Sorted => H::hash_pair(left, right),
+            PairHashing::Positional => H::hash_concat(left, right),
+        }
+    }
+
+    /// Builds the sequential layout: leaves first, then every level up to the root.
+    fn from_sequential_leaves(leaves: Vec<Hash32>, pair_hashing: PairHashing) -> Self {
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = Self::hash_level(pair_hashing, level);
+            levels.push(parents);
+        }
+        Self::from_sequential_levels(levels, pair_hashing)
+    }
+
+    /// Hashes the nodes of a level in pairs, an odd last node being promoted unchanged.
+    fn hash_level(pair_hashing: PairHashing, level: &[Hash32]) -> Vec<Hash32> {
+        let parent = |pair: &[Hash32]| match pair {
+            [left, right] => Self::hash_children(pair_hashing, left, right),
+            _ => pair[0],
+        };
+        #[cfg(feature = "parallel")]
+        return level.par_chunks(2).map(parent).collect();
+        #[cfg(not(feature = "parallel"))]
+        return level.chunks(2).map(parent).collect();
+    }
+
+    /// Lays out already hashed levels, from the leaves up to the root, as the graph of the sequential layout.
+    ///
+    /// Each level is stored after the previous one and node `i` of a level is the parent of nodes `2i`
+    /// and `2i + 1` of the level below, an odd last node being its only child.
+    fn from_sequential_levels(levels: Vec<Vec<Hash32>>, pair_hashing: PairHashing) -> Self {
+        let node_count = levels.iter().map(Vec::len).sum();
+        let mut graph = DiGraph::with_capacity(node_count, node_count);
+        let leaf_count = levels[0].len();
+        let leaf_positions = leaf_positions(levels[0].iter().copied().enumerate());
+        let mut offset = 0;
+        for (depth, level) in levels.iter().enumerate() {
+            for node_data in level {
+                graph.add_node(*node_data);
+            }
+            if depth > 0 {
+                let children_offset = offset - levels[depth - 1].len();
+                for child in 0..levels[depth - 1].len() {
+                    graph.add_edge(
+                        NodeIndex::new(offset + child / 2),
+                        NodeIndex::new(children_offset + child),
+                        (),
+                    );
                 }
-                new_indices.push(current_index);
             }
-            nodes = new_level;
-            previous_layer_indices = new_indices;
+            offset += level.len();
+        }
+
+        MerkleTree {
+            root: levels
+                .last()
+                .and_then(|level| level.first())
+                .map(|data| MerkleNode { data: *data }),
+            graph,
+            layout: TreeLayout::Sequential,
+            pair_hashing,
+            values: Vec::new(),
+            leaf_encoding: Vec::new(),
+            value_indices: (0..leaf_count).collect(),
+            leaf_positions,
+            root_index: node_count - 1,
+            hasher: PhantomData,
         }
+    }
 
-        let root_data = nodes[0].clone();
-        let root_node = MerkleNode { data: root_data };
+    /// Builds the standard layout: leaves sorted by hash and stored at the end of a complete binary tree.
+    fn from_standard_leaves(leaves: Vec<Hash32>, pair_hashing: PairHashing) -> Self {
+        let mut indexed: Vec<(usize, Hash32)> = leaves.into_iter().enumerate().collect();
+        indexed.sort_by_key(|a| a.1);
 
-        Ok(MerkleTree {
-            root: Some(root_node),
+        let tree_len = 2 * indexed.len() - 1;
+        let mut nodes = vec![Hash32::default(); tree_len];
+        let mut value_indices = vec![0; indexed.len()];
+        for (i, (value_index, leaf)) in indexed.into_iter().enumerate() {
+            nodes[tree_len - 1 - i] = leaf;
+            value_indices[value_index] = tree_len - 1 - i;
+        }
+        // Level `d` holds the nodes `2^d - 1..2^(d + 1) - 1`, whose children are all in later levels.
+        let internal = tree_len / 2;
+        for depth in (0..internal.next_power_of_two().trailing_zeros() as usize + 1).rev() {
+            let (start, end) = ((1 << depth) - 1, ((2 << depth) - 1).min(internal));
+            if start >= end {
+                continue;
+            }
+            let (parents, children) = nodes.split_at_mut(end);
+            let parent = |(i, node): (usize, &mut Hash32)| {
+                let left = 2 * (start + i) + 1 - end;
+                *node = Self::hash_children(pair_hashing, &children[left], &children[left + 1]);
+            };
+            #[cfg(feature = "parallel")]
+            parents[start..].par_iter_mut().enumerate().for_each(parent);
+            #[cfg(not(feature = "parallel"))]
+            parents[start..].iter_mut().enumerate().for_each(parent);
+        }
+
+        let leaf_positions = leaf_positions(
+            value_indices
+                .iter()
+                .map(|&tree_index| nodes[tree_index])
+                .enumerate(),
+        );
+
+        let mut graph = DiGraph::with_capacity(tree_len, tree_len - 1);
+        for node_data in &nodes {
+            graph.add_node(*node_data);
+        }
+        for i in 0..tree_len / 2 {
+            graph.add_edge(NodeIndex::new(i), NodeIndex::new(2 * i + 1), ());
+            graph.add_edge(NodeIndex::new(i), NodeIndex::new(2 * i + 2), ());
+        }
+
+        MerkleTree {
+            root: Some(MerkleNode { data: nodes[0] }),
             graph,
-        })
+            layout: TreeLayout::Standard,
+            pair_hashing,
+            values: Vec::new(),
+            leaf_encoding: Vec::new(),
+            value_indices,
+            leaf_positions,
+            root_index: 0,
+            hasher: PhantomData,
+        }
+    }
+
+    fn with_values(mut self, values: Vec<Vec<String>>, leaf_encoding: Vec<String>) -> Self {
+        self.values = values;
+        self.leaf_encoding = leaf_encoding;
+        self
+    }
+
+    /// Returns the graph index of the leaf built from `values[value_index]`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(index)` if `value_index` is within the values of the tree.
+    /// - `None` otherwise.
+    pub fn tree_index(&self, value_index: usize) -> Option<usize> {
+        self.value_indices.get(value_index).copied()
+    }
+
+    /// Returns the number of leaves of the tree.
+    pub fn leaf_count(&self) -> usize {
+        self.value_indices.len()
+    }
+
+    /// Returns the number of levels between the root and the deepest leaf.
+    ///
+    /// Both layouts place their deepest leaf `ceil(log2(leaf_count))` levels below the root.
+    pub fn depth(&self) -> usize {
+        self.leaf_count().next_power_of_two().trailing_zeros() as usize
     }
 
     /// Locates the index of a specific leaf based on its hash.
     ///
+    /// Only leaves are matched, an internal node with the same hash is never returned.
+    ///
     /// # Returns
     ///
     /// - `Some(index)` if the leaf with the specified hash is found.
     /// - `None` if the leaf with the specified hash is not found.
-    pub fn locate_leaf(&self, target_hash: &String) -> Option<usize> {
-        for (index, node_data) in self.graph.raw_nodes().iter().enumerate() {
-            if &node_data.weight == target_hash {
-                return Some(index);
+    pub fn locate_leaf(&self, target_hash: &Hash32) -> Option<usize> {
+        self.leaf_positions
+            .get(target_hash)
+            .and_then(|indices| indices.first())
+            .map(|&value_index| self.value_indices[value_index])
+    }
+
+    /// Locates the index of the leaf built from `value`.
+    ///
+    /// The value is hashed the same way the tree hashed its leaves, see [`MerkleTree::hash_value`].
+    ///
+    /// # Returns
+    ///
+    /// - `Some(index)` if a leaf was built from the value.
+    /// - `None` if the value can't be hashed or isn't part of the tree.
+    pub fn locate_value<S: AsRef<str>>(&self, value: &[S]) -> Option<usize> {
+        self.locate_leaf(&self.hash_value(value).ok()?)
+    }
+
+    /// Computes the leaf hash of `value` the way the leaves of this tree were hashed.
+    ///
+    /// A tree built from raw strings expects a single value, a tree built with [`MerkleTree::of`]
+    /// expects one value per type of its leaf encoding.
+    ///
+    /// # Errors
+    ///
+    /// - When the value can't be encoded.
+    pub fn hash_value<S: AsRef<str>>(&self, value: &[S]) -> Result<Hash32, MerkleError> {
+        if !self.leaf_encoding.is_empty() {
+            return H::standard_leaf_hash_typed(&self.leaf_encoding, value);
+        }
+        let raw = match value {
+            [raw] => raw.as_ref(),
+            _ => return Err(AbiError::LengthMismatch(1, value.len()).into()),
+        };
+        match self.layout {
+            TreeLayout::Sequential => H::leaf_hash(raw),
+            TreeLayout::Standard => H::standard_leaf_hash(raw),
+        }
+    }
+
+    /// Replaces the value at `index` and rehashes only the nodes on the path of its leaf.
+    ///
+    /// # Errors
+    ///
+    /// - When the tree doesn't use the [`TreeLayout::Sequential`] layout, whose leaves keep their
+    ///   position. Standard trees sort their leaves, so a changed leaf has to be rebuilt with the others.
+    /// - When `index` isn't the index of a value, or the value can't be hashed.
+    ///
+    /// # Returns
+    ///
+    /// - The previous value.
+    pub fn update_leaf<S: AsRef<str>>(
+        &mut self,
+        index: usize,
+        value: &[S],
+    ) -> Result<Vec<String>, MerkleError> {
+        self.check_sequential("Updating leaves")?;
+        if index >= self.leaf_count() {
+            return Err(MerkleError::InvalidIndex(index));
+        }
+        let leaf = self.hash_value(value)?;
+        let node = NodeIndex::new(self.value_indices[index]);
+        self.forget_leaf(self.graph[node], index);
+        self.remember_leaf(leaf, index);
+        self.graph[node] = leaf;
+        self.rehash_path(node);
+        Ok(std::mem::replace(
+            &mut self.values[index],
+            value.iter().map(|v| v.as_ref().to_string()).collect(),
+        ))
+    }
+
+    /// Appends a value, adding its leaf and the parents it needs to the end of the graph and hashing
+    /// only the nodes on its path.
+    ///
+    /// The other nodes keep their graph index, so the levels of the graph are no longer stored one after
+    /// the other. [`MerkleTree::dump`] still writes the nodes in the order of the layout.
+    ///
+    /// # Errors
+    ///
+    /// - When the tree doesn't use the [`TreeLayout::Sequential`] layout.
+    /// - When the value can't be hashed.
+    ///
+    /// # Returns
+    ///
+    /// - The index of the new value.
+    pub fn push_leaf<S: AsRef<str>>(&mut self, value: &[S]) -> Result<usize, MerkleError> {
+        self.check_sequential("Pushing leaves")?;
+        let leaf = self.hash_value(value)?;
+        let index = self.leaf_count();
+        let mut last = NodeIndex::new(self.value_indices[index - 1]);
+        let mut node = self.graph.add_node(leaf);
+        self.value_indices.push(node.index());
+        self.remember_leaf(leaf, index);
+        self.values
+            .push(value.iter().map(|v| v.as_ref().to_string()).collect());
+
+        // Climb the right edge of the tree until a node has room for a second child.
+        loop {
+            match self.graph_parent(last) {
+                Some(parent) if self.children(parent.index()).len() == 1 => {
+                    self.graph.add_edge(parent, node, ());
+                    break;
+                }
+                Some(parent) => {
+                    let promoted = self.graph.add_node(self.graph[node]);
+                    self.graph.add_edge(promoted, node, ());
+                    (last, node) = (parent, promoted);
+                }
+                None => {
+                    let root = self.graph.add_node(Hash32::default());
+                    self.graph.add_edge(root, last, ());
+                    self.graph.add_edge(root, node, ());
+                    self.root_index = root.index();
+                    break;
+                }
+            }
+        }
+        self.rehash_path(NodeIndex::new(self.value_indices[index]));
+        Ok(index)
+    }
+
+    /// Removes the value at `index` by moving the last value in its place, like [`Vec::swap_remove`].
+    ///
+    /// Only the paths of the moved leaf and of the last leaf are rehashed. The last leaf and the parents
+    /// left without children are removed from the graph, and the last nodes of the graph take their
+    /// graph indices, the other nodes keeping theirs.
+    ///
+    /// # Errors
+    ///
+    /// - When the tree doesn't use the [`TreeLayout::Sequential`] layout.
+    /// - When `index` isn't the index of a value.
+    /// - When the value is the only one of the tree, which can't be empty.
+    ///
+    /// # Returns
+    ///
+    /// - The removed value.
+    pub fn remove_leaf(&mut self, index: usize) -> Result<Vec<String>, MerkleError> {
+        self.check_sequential("Removing leaves")?;
+        if index >= self.leaf_count() {
+            return Err(MerkleError::InvalidIndex(index));
+        }
+        if self.leaf_count() == 1 {
+            return Err(MerkleError::EmptyInput);
+        }
+        let last = self.leaf_count() - 1;
+        let moved = NodeIndex::new(self.value_indices[index]);
+        let last_leaf = NodeIndex::new(self.value_indices[last]);
+        let last_hash = self.graph[last_leaf];
+        self.forget_leaf(self.graph[moved], index);
+        self.forget_leaf(last_hash, last);
+        if index != last {
+            self.remember_leaf(last_hash, index);
+            self.graph[moved] = last_hash;
+        }
+        self.value_indices.pop();
+        let removed = self.values.swap_remove(index);
+
+        // The last leaf goes with the parents it was the only child of, and the root when it's left
+        // with a single child.
+        let mut removed_nodes = vec![last_leaf];
+        let mut node = last_leaf;
+        let mut parent = self.graph_parent(node);
+        while let Some(single) = parent.filter(|&p| self.children(p.index()).len() == 1) {
+            removed_nodes.push(single);
+            node = single;
+            parent = self.graph_parent(node);
+        }
+        let parent = parent.expect("The tree keeps a leaf, so the last leaf has a parent");
+        self.detach(&removed_nodes);
+        if parent.index() == self.root_index {
+            if let [child] = self.children(parent.index())[..] {
+                removed_nodes.push(parent);
+                self.detach(&[parent]);
+                self.root_index = child;
             }
         }
-        None
+
+        if index != last {
+            self.rehash_path(moved);
+        }
+        if !removed_nodes.contains(&parent) {
+            let child = self.children(parent.index())[0];
+            self.rehash_path(NodeIndex::new(child));
+        }
+        self.root = Some(MerkleNode {
+            data: self.graph[NodeIndex::new(self.root_index)],
+        });
+
+        removed_nodes.sort_unstable_by(|a, b| b.cmp(a));
+        for node in removed_nodes {
+            self.remove_detached(node);
+        }
+        Ok(removed)
+    }
+
+    fn check_sequential(&self, operation: &str) -> Result<(), MerkleError> {
+        match self.layout {
+            TreeLayout::Sequential => Ok(()),
+            TreeLayout::Standard => Err(MerkleError::UnsupportedLayout(operation.to_string())),
+        }
+    }
+
+    /// Removes `index` from the value indices of `leaf`.
+    fn forget_leaf(&mut self, leaf: Hash32, index: usize) {
+        if let Some(indices) = self.leaf_positions.get_mut(&leaf) {
+            indices.retain(|&i| i != index);
+            if indices.is_empty() {
+                self.leaf_positions.remove(&leaf);
+            }
+        }
+    }
+
+    /// Adds `index` to the value indices of `leaf`, keeping them sorted.
+    fn remember_leaf(&mut self, leaf: Hash32, index: usize) {
+        let indices = self.leaf_positions.entry(leaf).or_default();
+        if let Err(position) = indices.binary_search(&index) {
+            indices.insert(position, index);
+        }
+    }
+
+    /// Rehashes every ancestor of `node` from its children, then updates the root.
+    fn rehash_path(&mut self, node: NodeIndex) {
+        let mut current = node;
+        while let Some(parent) = self.graph_parent(current) {
+            self.graph[parent] = match self.children(parent.index())[..] {
+                [left, right] => Self::hash_children(
+                    self.pair_hashing,
+                    &self.graph[NodeIndex::new(left)],
+                    &self.graph[NodeIndex::new(right)],
+                ),
+                _ => self.graph[current],
+            };
+            current = parent;
+        }
+        self.root = Some(MerkleNode {
+            data: self.graph[current],
+        });
+    }
+
+    fn graph_parent(&self, node: NodeIndex) -> Option<NodeIndex> {
+        self.graph
+            .neighbors_directed(node, petgraph::Incoming)
+            .next()
+    }
+
+    /// Removes the edges of `nodes`, leaving them out of the tree.
+    fn detach(&mut self, nodes: &[NodeIndex]) {
+        for &node in nodes {
+            for direction in [petgraph::Incoming, petgraph::Outgoing] {
+                while let Some(edge) = self.graph.first_edge(node, direction) {
+                    self.graph.remove_edge(edge);
+                }
+            }
+        }
+    }
+
+    /// Removes a detached node from the graph, which moves the last node of the graph to its index.
+    ///
+    /// The moved node keeps its side: when it lands on the other side of its sibling, the two swap indices.
+    fn remove_detached(&mut self, node: NodeIndex) {
+        let last = NodeIndex::new(self.graph.node_count() - 1);
+        if node == last {
+            self.graph.remove_node(node);
+            return;
+        }
+        let value_index = self.value_index(last);
+        let sibling = self.graph_parent(last).and_then(|parent| {
+            let children = self.children(parent.index());
+            let sibling = children.into_iter().find(|&child| child != last.index())?;
+            Some((NodeIndex::new(sibling), sibling < last.index()))
+        });
+        self.graph.remove_node(node);
+        if self.root_index == last.index() {
+            self.root_index = node.index();
+        }
+        if let Some(value_index) = value_index {
+            self.value_indices[value_index] = node.index();
+        }
+        if let Some((sibling, is_right)) = sibling {
+            if is_right != (sibling < node) {
+                self.swap_nodes(node, sibling);
+            }
+        }
+    }
+
+    /// Swaps the graph indices of two nodes, with their hashes and edges.
+    fn swap_nodes(&mut self, a: NodeIndex, b: NodeIndex) {
+        let swap = |node: NodeIndex| match node {
+            node if node == a => b,
+            node if node == b => a,
+            node => node,
+        };
+        let edges: Vec<(NodeIndex, NodeIndex)> = [a, b]
+            .into_iter()
+            .flat_map(|node| {
+                let parents = self.graph.neighbors_directed(node, petgraph::Incoming);
+                let children = self.graph.neighbors_directed(node, petgraph::Outgoing);
+                parents
+                    .map(move |parent| (parent, node))
+                    .chain(children.map(move |child| (node, child)))
+            })
+            .collect();
+        let value_indices = [self.value_index(a), self.value_index(b)];
+        self.detach(&[a, b]);
+        for (parent, child) in edges {
+            if self.graph.find_edge(swap(parent), swap(child)).is_none() {
+                self.graph.add_edge(swap(parent), swap(child), ());
+            }
+        }
+        let hash = self.graph[a];
+        self.graph[a] = self.graph[b];
+        self.graph[b] = hash;
+        for (value_index, node) in value_indices.into_iter().zip([b, a]) {
+            if let Some(value_index) = value_index {
+                self.value_indices[value_index] = node.index();
+            }
+        }
+    }
+
+    /// Returns the index of the value a leaf was built from, `None` if `node` isn't a leaf.
+    fn value_index(&self, node: NodeIndex) -> Option<usize> {
+        if !self.children(node.index()).is_empty() {
+            return None;
+        }
+        self.leaf_positions
+            .get(&self.graph[node])?
+            .iter()
+            .copied()
+            .find(|&value_index| self.value_indices.get(value_index) == Some(&node.index()))
     }
 
     /// Generates a proof of inclusion for a specific leaf.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// - When the specified leaf index is out of bounds.
+    /// - When the specified leaf index is out of bounds or doesn't point to a leaf.
     ///
     /// # Returns
     ///
     /// - A vector containing hashes that make up the proof for the specified leaf.
-    pub fn generate_proof(&self, leaf_index: usize) -> Vec<String> {
-        let mut proof = Vec::new();
+    pub fn generate_proof(&self, leaf_index: usize) -> Result<Vec<Hash32>, MerkleError> {
+        Ok(self.generate_directed_proof(leaf_index)?.siblings)
+    }
+
+    /// Generates a proof of inclusion for a specific leaf, with the side of each sibling.
+    ///
+    /// The sides are needed to verify a tree built with [`PairHashing::Positional`], see
+    /// [`crate::utils::verify::verify_positional_proof`].
+    ///
+    /// # Errors
+    ///
+    /// - When the specified leaf index is out of bounds or doesn't point to a leaf.
+    pub fn generate_directed_proof(&self, leaf_index: usize) -> Result<DirectedProof, MerkleError> {
+        if !self.is_leaf(leaf_index) {
+            return Err(MerkleError::InvalidIndex(leaf_index));
+        }
+        let mut siblings = Vec::new();
+        let mut sides = Vec::new();
         let mut current_index = leaf_index;
 
         while let Some(parent_edge) = self
@@ -108,19 +782,264 @@ impl MerkleTree {
         {
             let parent = parent_edge.source();
             for edge in self.graph.edges_directed(parent, petgraph::Outgoing) {
-                if edge.target().index() != current_index {
-                    proof.push(format!("0x{}", self.graph[edge.target()].clone()));
+                let sibling = edge.target().index();
+                if sibling != current_index {
+                    siblings.push(self.graph[edge.target()]);
+                    sides.push(if sibling < current_index {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    });
                 }
             }
             current_index = parent.index();
         }
-        proof
+        Ok(DirectedProof { siblings, sides })
+    }
+
+    /// Returns the nodes a valid proof of the leaf computes: the leaf, then the parent reached after
+    /// each sibling of [`MerkleTree::generate_proof`], the last one being the root.
+    ///
+    /// Compare it with [`crate::utils::verify::ProofCheck::divergence`] to find where a proof goes wrong.
+    ///
+    /// # Errors
+    ///
+    /// - When the specified leaf index is out of bounds or doesn't point to a leaf.
+    pub fn proof_path(&self, leaf_index: usize) -> Result<Vec<Hash32>, MerkleError> {
+        if !self.is_leaf(leaf_index) {
+            return Err(MerkleError::InvalidIndex(leaf_index));
+        }
+        let mut path = vec![self.graph[NodeIndex::new(leaf_index)]];
+        let mut current = NodeIndex::new(leaf_index);
+        while let Some(parent_edge) = self
+            .graph
+            .edges_directed(current, petgraph::Incoming)
+            .next()
+        {
+            current = parent_edge.source();
+            // A promoted node has no sibling, its parent adds no step to the proof.
+            if self
+                .graph
+                .edges_directed(current, petgraph::Outgoing)
+                .count()
+                > 1
+            {
+                path.push(self.graph[current]);
+            }
+        }
+        Ok(path)
+    }
+
+    /// Generates the proof of every value of the tree, in the order of `values`.
+    ///
+    /// The proofs are built in a single pass over the levels, from the root down: the proof of a node is its
+    /// sibling followed by the proof of its parent, so each sibling is looked up once and the part of
+    /// a proof above a node is shared by every leaf below it instead of being walked again per leaf.
+    ///
+    /// # Returns
+    ///
+    /// - A vector holding, for each value, the same proof [`MerkleTree::generate_proof`] returns for its leaf.
+    pub fn all_proofs(&self) -> Vec<Vec<Hash32>> {
+        let node_count = self.graph.node_count();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+        for edge in self.graph.raw_edges() {
+            children[edge.source().index()].push(edge.target().index());
+        }
+
+        let mut leaf_proofs: Vec<Vec<Hash32>> = vec![Vec::new(); node_count];
+        let mut level: Vec<(usize, Vec<Hash32>)> = match node_count {
+            0 => Vec::new(),
+            _ => vec![(self.root_index(), Vec::new())],
+        };
+        while !level.is_empty() {
+            let mut next = Vec::with_capacity(level.len() * 2);
+            for (node, proof) in level {
+                let node_children = &children[node];
+                if node_children.is_empty() {
+                    leaf_proofs[node] = proof;
+                    continue;
+                }
+                for &child in node_children {
+                    let mut child_proof = Vec::with_capacity(proof.len() + 1);
+                    child_proof.extend(
+                        node_children
+                            .iter()
+                            .filter(|&&sibling| sibling != child)
+                            .map(|&sibling| self.graph[NodeIndex::new(sibling)]),
+                    );
+                    child_proof.extend_from_slice(&proof);
+                    next.push((child, child_proof));
+                }
+            }
+            level = next;
+        }
+
+        self.value_indices
+            .iter()
+            .map(|&leaf_index| std::mem::take(&mut leaf_proofs[leaf_index]))
+            .collect()
+    }
+
+    /// Returns `true` if the graph index points to a leaf, a node without children.
+    fn is_leaf(&self, index: usize) -> bool {
+        index < self.graph.node_count()
+            && self
+                .graph
+                .edges_directed(NodeIndex::new(index), petgraph::Outgoing)
+                .next()
+                .is_none()
+    }
+
+    /// Generates a multiproof for several leaves, compatible with `MerkleProof.multiProofVerify`.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf_indices` - The indices of the leaves to prove, as returned by `locate_leaf`.
+    ///
+    /// # Errors
+    ///
+    /// - When the tree doesn't use the [`TreeLayout::Standard`] layout, or hashes pairs positionally.
+    /// - When an index doesn't point to a leaf, or is given more than once.
+    ///
+    /// # Returns
+    ///
+    /// - A [`MultiProof`] with the leaves sorted the way the verifier consumes them.
+    pub fn generate_multi_proof(&self, leaf_indices: &[usize]) -> Result<MultiProof, MerkleError> {
+        if self.layout != TreeLayout::Standard || self.pair_hashing != PairHashing::Sorted {
+            return Err(MerkleError::UnsupportedLayout(String::from("Multiproof")));
+        }
+        let mut indices = leaf_indices.to_vec();
+        if let Some(index) = indices.iter().find(|index| !self.is_leaf(**index)) {
+            return Err(MerkleError::InvalidIndex(*index));
+        }
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        if let Some(pair) = indices.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(MerkleError::InvalidIndex(pair[0]));
+        }
+
+        let node = |index: usize| self.graph[NodeIndex::new(index)];
+        let mut stack: VecDeque<usize> = indices.iter().copied().collect();
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+        while let Some(&j) = stack.front() {
+            if j == 0 {
+                break;
+            }
+            stack.pop_front();
+            let sibling = if j % 2 == 1 { j + 1 } else { j - 1 };
+            if stack.front() == Some(&sibling) {
+                proof_flags.push(true);
+                stack.pop_front();
+            } else {
+                proof_flags.push(false);
+                proof.push(node(sibling));
+            }
+            stack.push_back((j - 1) / 2);
+        }
+        if indices.is_empty() {
+            proof.push(node(0));
+        }
+
+        Ok(MultiProof {
+            leaves: indices.into_iter().map(node).collect(),
+            proof,
+            proof_flags,
+        })
     }
 }
 
+/// Maps each leaf hash to the indices of its values, in the order they're seen.
+fn leaf_positions(leaves: impl Iterator<Item = (usize, Hash32)>) -> HashMap<Hash32, Vec<usize>> {
+    let mut positions: HashMap<Hash32, Vec<usize>> = HashMap::new();
+    for (index, leaf) in leaves {
+        positions.entry(leaf).or_default().push(index);
+    }
+    positions
+}
+
+/// Hashes every leaf, reporting the index of the first leaf that can't be hashed.
+fn hash_leaves<T: Sync>(
+    leaves: &[T],
+    hash: impl Fn(&T) -> Result<Hash32, MerkleError> + Sync,
+) -> Result<Vec<Hash32>, MerkleError> {
+    if leaves.is_empty() {
+        return Err(MerkleError::EmptyInput);
+    }
+    #[cfg(feature = "parallel")]
+    if let Ok(hashes) = leaves.par_iter().map(&hash).collect() {
+        return Ok(hashes);
+    }
+    // Hashing serially finds the first invalid leaf, rayon may stop at any of them.
+    leaves
+        .iter()
+        .enumerate()
+        .map(|(index, leaf)| {
+            hash(leaf).map_err(|e| MerkleError::LeafEncoding(index, e.to_string()))
+        })
+        .collect()
+}
+
+impl<H> MerkleTree<H> {
+    /// Returns the graph index of the root: the last node of the sequential layout, the first of the standard one.
+    ///
+    /// Pushing and removing leaves moves the root of a sequential tree elsewhere in the graph.
+    pub fn root_index(&self) -> usize {
+        self.root_index
+    }
+
+    /// Returns the graph indices of the children of a node, left child first.
+    ///
+    /// A leaf has no children, and a node promoted from an odd level has a single one.
+    pub fn children(&self, index: usize) -> Vec<usize> {
+        let mut children: Vec<usize> = self
+            .graph
+            .neighbors_directed(NodeIndex::new(index), petgraph::Outgoing)
+            .map(|child| child.index())
+            .collect();
+        children.sort_unstable();
+        children
+    }
+
+    /// Returns the graph index of every leaf with the value it was built from, in input order.
+    pub fn leaf_values(&self) -> impl Iterator<Item = (usize, &[String])> {
+        self.value_indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().map(Vec::as_slice))
+    }
+
+    /// Returns the graph index of the parent of a node, `None` for the root.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.graph
+            .neighbors_directed(NodeIndex::new(index), petgraph::Incoming)
+            .next()
+            .map(|parent| parent.index())
+    }
+}
+
+fn raw_values(data: &[String]) -> Vec<Vec<String>> {
+    data.iter().map(|value| vec![value.clone()]).collect()
+}
+
+/// Returns `count` distinct raw values, `0x01` onwards, to build the trees of the tests from.
+#[cfg(test)]
+pub(crate) fn test_values(count: usize) -> Vec<String> {
+    (1..=count).map(|i| format!("0x{:02x}", i)).collect()
+}
+
+/// Returns the sequential tree of `data` built from scratch, to compare other ways of building it against.
+#[cfg(test)]
+pub(crate) fn reference_tree(data: &[String], pair_hashing: PairHashing) -> MerkleTree {
+    MerkleTree::new(data)
+        .expect("Failed to create Merkle Tree")
+        .with_pair_hashing(pair_hashing)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::bytes::hash_pair;
+    use crate::utils::keccak::{leaf_hash, standard_leaf_hash};
 
     #[test]
     fn test_hash_pair() {
@@ -132,4 +1051,396 @@ mod tests {
             "4f1bf293e60209d407901e10ebbdb0da6faaf7ae860c3816321563708f6619bf"
         )
     }
+
+    /// in javascript
+    /// StandardMerkleTree.of(values, ["address", "uint256"]).root
+    #[test]
+    fn test_standard_tree_matches_openzeppelin() {
+        let data = vec![
+            String::from("0x1111111111111111111111111111111111111111, 5000000000000000000"),
+            String::from("0x2222222222222222222222222222222222222222, 2500000000000000000"),
+        ];
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        assert_eq!(tree.layout, TreeLayout::Standard);
+        assert_eq!(
+            tree.root.expect("No root found").data.to_hex(),
+            "d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77"
+        );
+    }
+
+    #[test]
+    fn test_typed_tree_matches_openzeppelin() {
+        let values = vec![
+            vec![
+                String::from("0x1111111111111111111111111111111111111111"),
+                String::from("5000000000000000000"),
+            ],
+            vec![
+                String::from("0x2222222222222222222222222222222222222222"),
+                String::from("2500000000000000000"),
+            ],
+        ];
+        let tree =
+            MerkleTree::of(&values, &["address", "uint256"]).expect("Failed to create Merkle Tree");
+        assert_eq!(
+            tree.root.expect("No root found").data.to_hex(),
+            "d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77"
+        );
+        assert!(MerkleTree::of(&values, &["address"]).is_err());
+    }
+
+    #[test]
+    fn test_standard_tree_layout() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123", "0x456", "0x789"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        assert_eq!(tree.graph.node_count(), 2 * data.len() - 1);
+        let leaves: Vec<&Hash32> = tree.graph.raw_nodes()[data.len() - 1..]
+            .iter()
+            .map(|node| &node.weight)
+            .collect();
+        assert!(leaves.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_standard_levels_match_bottom_up_hashing() {
+        for count in 1..=70 {
+            let data: Vec<String> = (1..=count).map(|i| format!("0x{:04x}", i)).collect();
+            let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+            let mut nodes: Vec<Hash32> = tree.graph.raw_nodes().iter().map(|n| n.weight).collect();
+            for i in (0..nodes.len() / 2).rev() {
+                nodes[i] = Keccak256::hash_pair(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+            }
+            assert!(tree.graph.raw_nodes().iter().map(|n| n.weight).eq(nodes));
+        }
+    }
+
+    #[test]
+    fn test_multi_proof_single_leaf_matches_proof() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123", "0x456", "0x789"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        let index = tree
+            .locate_leaf(&standard_leaf_hash("0x123").expect("Keccak error."))
+            .expect("Failed to locate leaf");
+        let multi_proof = tree
+            .generate_multi_proof(&[index])
+            .expect("Failed to generate multiproof");
+        assert_eq!(
+            multi_proof.proof,
+            tree.generate_proof(index)
+                .expect("Failed to generate proof")
+        );
+        assert!(multi_proof.proof_flags.iter().all(|flag| !flag));
+    }
+
+    #[test]
+    fn test_multi_proof_errors() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let sequential = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        assert!(sequential.generate_multi_proof(&[0]).is_err());
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        assert!(tree.generate_multi_proof(&[0]).is_err());
+        assert!(tree.generate_multi_proof(&[3, 3]).is_err());
+        assert!(tree.generate_multi_proof(&[5]).is_err());
+    }
+
+    #[test]
+    fn test_depth() {
+        for count in 1..=9 {
+            let data = test_values(count);
+            let expected = (count as f64).log2().ceil() as usize;
+            for tree in [
+                MerkleTree::new(&data).expect("Failed to create Merkle Tree"),
+                MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree"),
+            ] {
+                assert_eq!(tree.leaf_count(), count);
+                assert_eq!(tree.depth(), expected);
+                let index = tree.tree_index(0).expect("Failed to locate leaf");
+                assert!(tree.generate_proof(index).unwrap().len() <= expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_proofs_match_generate_proof() {
+        for count in 1..=9 {
+            let data = test_values(count);
+            for tree in [
+                MerkleTree::new(&data).expect("Failed to create Merkle Tree"),
+                MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree"),
+            ] {
+                let proofs = tree.all_proofs();
+                assert_eq!(proofs.len(), count);
+                for (value_index, proof) in proofs.iter().enumerate() {
+                    let index = tree.tree_index(value_index).expect("Failed to locate leaf");
+                    assert_eq!(
+                        proof,
+                        &tree
+                            .generate_proof(index)
+                            .expect("Failed to generate proof")
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_positional_pair_hashing() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123", "0x456"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let sorted = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        let sorted_root = sorted.root.as_ref().expect("No root found").data;
+        let tree = sorted.with_pair_hashing(PairHashing::Positional);
+        assert_eq!(tree.pair_hashing, PairHashing::Positional);
+        assert_eq!(tree.values.len(), data.len());
+
+        let leaves: Vec<Hash32> = data.iter().map(|d| leaf_hash(d).unwrap()).collect();
+        let expected = Keccak256::hash_concat(
+            &Keccak256::hash_concat(&leaves[0], &leaves[1]),
+            &Keccak256::hash_concat(&leaves[2], &leaves[3]),
+        );
+        assert_eq!(tree.root.as_ref().expect("No root found").data, expected);
+        assert_ne!(expected, sorted_root);
+
+        for value_index in 0..data.len() {
+            let proof = tree
+                .generate_directed_proof(value_index)
+                .expect("Failed to generate proof");
+            assert_eq!(proof.path_bits(), value_index as u64);
+        }
+        let tree = tree.with_pair_hashing(PairHashing::Sorted);
+        assert_eq!(tree.root.expect("No root found").data, sorted_root);
+
+        let standard = MerkleTree::new_standard(&data)
+            .expect("Failed to create Merkle Tree")
+            .with_pair_hashing(PairHashing::Positional);
+        assert!(standard.generate_multi_proof(&[3]).is_err());
+        let proof = standard
+            .generate_directed_proof(3)
+            .expect("Failed to generate proof");
+        assert_eq!(proof.sides, vec![Side::Right, Side::Right]);
+    }
+
+    fn assert_same_tree(tree: &MerkleTree, data: &[String]) {
+        let expected = reference_tree(data, tree.pair_hashing);
+        assert_eq!(tree.dump(), expected.dump());
+        assert_eq!(
+            tree.root.as_ref().unwrap().data,
+            expected.root.as_ref().unwrap().data
+        );
+        assert_eq!(tree.all_proofs(), expected.all_proofs());
+        for (value_index, value) in data.iter().enumerate() {
+            let index = tree.tree_index(value_index).unwrap();
+            assert_eq!(tree.locate_value(&[value]), Some(index));
+            assert_eq!(
+                tree.generate_directed_proof(index).unwrap(),
+                expected.generate_directed_proof(value_index).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_push_remove_leaf() {
+        for pair_hashing in [PairHashing::Sorted, PairHashing::Positional] {
+            let mut data = test_values(5);
+            let mut tree = MerkleTree::new(&data)
+                .expect("Failed to create Merkle Tree")
+                .with_pair_hashing(pair_hashing);
+
+            for (index, value) in [(0, "0xaa"), (4, "0xbb"), (2, "0x01")] {
+                let previous = tree.update_leaf(index, &[value]).unwrap();
+                assert_eq!(
+                    previous,
+                    vec![std::mem::replace(&mut data[index], value.to_string())]
+                );
+                assert_same_tree(&tree, &data);
+            }
+            for i in 0..5 {
+                let value = format!("0x{:02x}", 0x10 + i);
+                assert_eq!(tree.push_leaf(&[&value]).unwrap(), data.len());
+                data.push(value);
+                assert_same_tree(&tree, &data);
+            }
+            for index in [0, 8, 3, 1, 2, 0, 2, 1, 0] {
+                let removed = tree.remove_leaf(index).unwrap();
+                assert_eq!(removed, vec![data.swap_remove(index)]);
+                assert_same_tree(&tree, &data);
+            }
+            assert!(matches!(tree.remove_leaf(0), Err(MerkleError::EmptyInput)));
+            assert!(tree.update_leaf(1, &["0x01"]).is_err());
+            assert!(tree.update_leaf(0, &["not hex"]).is_err());
+        }
+
+        let data: Vec<String> = vec![String::from("0xabc"), String::from("0xdef")];
+        let mut standard = MerkleTree::new_standard(&data).unwrap();
+        assert!(matches!(
+            standard.update_leaf(0, &["0x01"]),
+            Err(MerkleError::UnsupportedLayout(_))
+        ));
+        assert!(standard.push_leaf(&["0x01"]).is_err());
+        assert!(standard.remove_leaf(0).is_err());
+    }
+
+    /// Returns the graph index of every node on the path from the leaf of `value_index` to the root.
+    fn path_indices(tree: &MerkleTree, value_index: usize) -> Vec<usize> {
+        let mut path = vec![tree.tree_index(value_index).unwrap()];
+        while let Some(parent) = tree.parent(path[path.len() - 1]) {
+            path.push(parent);
+        }
+        path
+    }
+
+    #[test]
+    fn test_push_remove_leaf_keep_graph() {
+        let mut data = test_values(6);
+        let mut tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        for value in ["0x07", "0x08", "0x09"] {
+            let nodes: Vec<Hash32> = tree.graph.node_weights().copied().collect();
+            let index = tree.push_leaf(&[value]).unwrap();
+            data.push(value.to_string());
+            let expected = MerkleTree::new(&data).unwrap();
+            assert_eq!(tree.graph.node_count(), expected.graph.node_count());
+            // Pushing only appends nodes, and rehashes the path of the new leaf.
+            let path = path_indices(&tree, index);
+            for (i, node) in nodes.iter().enumerate() {
+                if !path.contains(&i) {
+                    assert_eq!(tree.graph[NodeIndex::new(i)], *node);
+                }
+            }
+        }
+
+        for index in [2, 7, 0, 4] {
+            let nodes: Vec<Hash32> = tree.graph.node_weights().copied().collect();
+            let mut paths = path_indices(&tree, index);
+            paths.extend(path_indices(&tree, data.len() - 1));
+            tree.remove_leaf(index).unwrap();
+            data.swap_remove(index);
+            let expected = MerkleTree::new(&data).unwrap();
+            let node_count = expected.graph.node_count();
+            assert_eq!(tree.graph.node_count(), node_count);
+            // Off the two paths, a node keeps its index unless a node from the end of the graph took it.
+            let moved = &nodes[node_count..];
+            for (i, node) in nodes[..node_count].iter().enumerate() {
+                let hash = tree.graph[NodeIndex::new(i)];
+                if !paths.contains(&i) {
+                    assert!(hash == *node || moved.contains(&hash));
+                }
+            }
+            assert_same_tree(&tree, &data);
+        }
+    }
+
+    #[test]
+    fn test_update_leaf_with_duplicates() {
+        let data: Vec<String> = ["0xabc", "0xabc", "0xdef"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        tree.update_leaf(0, &["0x123"]).unwrap();
+        assert_eq!(tree.locate_value(&["0xabc"]), Some(1));
+        tree.update_leaf(2, &["0xabc"]).unwrap();
+        tree.update_leaf(1, &["0x456"]).unwrap();
+        assert_eq!(tree.locate_value(&["0xabc"]), Some(2));
+    }
+
+    #[test]
+    fn test_locate_leaf_ignores_internal_nodes() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        // The odd leaf is promoted unchanged, so its hash is also stored in an internal node.
+        let promoted = leaf_hash("0x123").expect("Keccak error.");
+        assert_eq!(tree.locate_leaf(&promoted), Some(2));
+        let internal = tree.graph[NodeIndex::new(3)];
+        assert_eq!(tree.locate_leaf(&internal), None);
+        assert_eq!(tree.locate_leaf(&tree.root.as_ref().unwrap().data), None);
+    }
+
+    #[test]
+    fn test_locate_value() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123", "0x456", "0x789"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        for tree in [
+            MerkleTree::new(&data).expect("Failed to create Merkle Tree"),
+            MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree"),
+        ] {
+            for (value_index, value) in data.iter().enumerate() {
+                assert_eq!(tree.locate_value(&[value]), tree.tree_index(value_index));
+            }
+            assert_eq!(tree.locate_value(&["0xfff"]), None);
+            assert_eq!(tree.locate_value(&["0xabc", "0xdef"]), None);
+        }
+
+        let values = vec![
+            vec![
+                String::from("0x1111111111111111111111111111111111111111"),
+                String::from("5000000000000000000"),
+            ],
+            vec![
+                String::from("0x2222222222222222222222222222222222222222"),
+                String::from("2500000000000000000"),
+            ],
+        ];
+        let tree =
+            MerkleTree::of(&values, &["address", "uint256"]).expect("Failed to create Merkle Tree");
+        assert_eq!(
+            tree.locate_value(&[
+                "0x2222222222222222222222222222222222222222",
+                "0x22b1c8c1227a0000"
+            ]),
+            tree.tree_index(1)
+        );
+        assert_eq!(tree.locate_value(&["not an address", "1"]), None);
+    }
+
+    #[test]
+    fn test_standard_tree_empty() {
+        assert!(matches!(
+            MerkleTree::new_standard(&[]),
+            Err(MerkleError::EmptyInput)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_leaf_reports_index() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0xxyz"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(matches!(
+            MerkleTree::new(&data),
+            Err(MerkleError::LeafEncoding(2, _))
+        ));
+        assert!(matches!(MerkleTree::new(&[]), Err(MerkleError::EmptyInput)));
+    }
+
+    #[test]
+    fn test_generate_proof_invalid_index() {
+        let data: Vec<String> = ["0xabc", "0xdef", "0x123"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        assert!(tree.generate_proof(0).is_ok());
+        assert!(matches!(
+            tree.generate_proof(3),
+            Err(MerkleError::InvalidIndex(3))
+        ));
+        assert!(tree.generate_proof(100).is_err());
+    }
 }
@@ -0,0 +1,441 @@
+//! # Solidity
+//!
+//! Provides a ready-to-compile Solidity contract verifying the proofs of a tree, with its root embedded.
+//!
+//! The contract hashes leaves and pairs exactly the way the tree did. Its leaf parameters follow how
+//! the leaves were built:
+//!
+//! - A tree built with a leaf encoding, like [`MerkleTree::of`](super::MerkleTree::of), takes one parameter
+//!   per type, ABI encoded with `abi.encode`.
+//! - Raw `address, amount` leaves take an `address account` and a `uint256 amount`, ABI encoded.
+//! - Raw hex leaves take an `address` when they are all 20 bytes long, a `bytes32` when they are all
+//!   32 bytes long, and `bytes` otherwise, hashed as they are.
+//!
+//! Standard trees hash the encoded leaf twice, like OpenZeppelin's `StandardMerkleTree`. Positional trees
+//! take the sides of the siblings as a `uint256 path`, see [`DirectedProof::path_bits`](super::DirectedProof::path_bits).
+
+use super::{MerkleTree, PairHashing, TreeLayout};
+use crate::utils::abi::AbiType;
+use crate::utils::errors::MerkleError;
+use crate::utils::hasher::MerkleHasher;
+use crate::utils::keccak::leaf_bytes;
+
+/// The verifier contract, filled in by [`MerkleTree::solidity_verifier`].
+macro_rules! contract_template {
+    () => {
+        "// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.4;
+
+/// @title {name}
+/// @notice Verifies proofs of a Merkle tree of {leaf_count} leaves.
+/// @dev Leaves are `{leaf_hash}`. Pairs are {pairs}.
+contract {name} {{
+    bytes32 public constant ROOT = {root};
+{claim_state}
+    /// @notice Hashes a leaf the way the tree hashed it.
+    function leafHash({parameters}) public pure returns (bytes32) {{
+        return {leaf_hash};
+    }}
+
+    /// @notice Returns `true` if `proof` shows the leaf is part of the tree.{path_doc}
+    function verify(bytes32[] calldata proof, {path_parameter}{parameters}) public pure returns (bool) {{
+        return processProof(proof, {path_argument}leafHash({arguments})) == ROOT;
+    }}
+{claim_function}
+    /// @notice Returns the root computed from a leaf and its proof.
+    function processProof(bytes32[] calldata proof, {path_parameter}bytes32 leaf) public pure returns (bytes32 node) {{
+        node = leaf;
+        for (uint256 i = 0; i < proof.length; i++) {{
+            node = {next_node};
+        }}
+    }}
+
+    function hashConcat(bytes32 left, bytes32 right) private pure returns (bytes32) {{
+        return {hash}(abi.encodePacked(left, right));
+    }}
+}}
+"
+    };
+}
+
+/// The `claim` function added by [`SolidityOptions::claim`].
+macro_rules! claim_template {
+    () => {
+        "
+    /// @notice Claims a leaf of the tree. Each leaf can be claimed once.
+    function claim(bytes32[] calldata proof, {path_parameter}{parameters}) public virtual {{
+        bytes32 leaf = leafHash({arguments});
+        if (claimed[leaf]) revert AlreadyClaimed(leaf);
+        if (processProof(proof, {path_argument}leaf) != ROOT) revert InvalidProof();
+        claimed[leaf] = true;
+        emit Claimed(leaf);
+    }}
+"
+    };
+}
+
+/// The state and events of the `claim` function.
+const CLAIM_STATE: &str = "
+    /// @notice Whether each leaf was claimed.
+    mapping(bytes32 => bool) public claimed;
+
+    event Claimed(bytes32 indexed leaf);
+
+    error AlreadyClaimed(bytes32 leaf);
+    error InvalidProof();
+";
+
+/// The contract generated by [`MerkleTree::solidity_verifier`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidityOptions {
+    /// The name of the contract.
+    pub contract_name: String,
+    /// Adds a `claim` function accepting each leaf once, on top of `verify`.
+    pub claim: bool,
+}
+
+impl Default for SolidityOptions {
+    fn default() -> Self {
+        SolidityOptions {
+            contract_name: String::from("MerkleVerifier"),
+            claim: false,
+        }
+    }
+}
+
+/// How the raw leaves of a tree built without a leaf encoding were decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RawLeaf {
+    AddressAmount,
+    Address,
+    Bytes32,
+    Bytes,
+}
+
+/// The parameters of a leaf and the Solidity expression encoding them.
+struct LeafParameters {
+    /// The type, with its data location, and the name of each parameter.
+    parameters: Vec<(String, String)>,
+    encoded: String,
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Returns the source of a Solidity contract verifying proofs of this tree.
+    ///
+    /// The contract holds the root as `ROOT` and exposes `leafHash`, `processProof` and `verify`, plus
+    /// `claim` when `options.claim` is set. It only depends on Solidity 0.8.4 or later.
+    ///
+    /// # Errors
+    ///
+    /// - When the hash function of the tree has no Solidity builtin.
+    /// - When the contract name isn't a Solidity identifier.
+    /// - When the leaf encoding holds a tuple, which can't be a function parameter.
+    /// - When raw `address, amount` leaves and raw hex leaves are mixed, reported with the index
+    ///   of the first leaf that differs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use eth_merkle_tree::tree::{MerkleTree, SolidityOptions};
+    ///
+    /// let values = vec![vec![String::from("0x1111111111111111111111111111111111111111"), String::from("5")]];
+    /// let tree = MerkleTree::of(&values, &["address", "uint256"]).expect("Tree creation error.");
+    /// let contract = tree.solidity_verifier(&SolidityOptions::default()).expect("Unsupported tree.");
+    /// assert!(contract.contains("keccak256(bytes.concat(keccak256(abi.encode(value0, value1))))"));
+    /// ```
+    pub fn solidity_verifier(&self, options: &SolidityOptions) -> Result<String, MerkleError> {
+        let hash = H::SOLIDITY_FUNCTION.ok_or_else(|| {
+            MerkleError::InvalidInput(String::from(
+                "The hash function of the tree has no Solidity builtin",
+            ))
+        })?;
+        let name = &options.contract_name;
+        if !is_identifier(name) {
+            return Err(MerkleError::InvalidInput(format!(
+                "{} isn't a valid contract name",
+                name
+            )));
+        }
+        let root = self.root.as_ref().ok_or(MerkleError::EmptyInput)?.data;
+        let leaf = self.leaf_parameters()?;
+        let leaf_hash = if self.leaf_encoding.is_empty() && self.layout == TreeLayout::Sequential {
+            format!("{}({})", hash, leaf.encoded)
+        } else {
+            format!("{}(bytes.concat({}({})))", hash, hash, leaf.encoded)
+        };
+        let positional = self.pair_hashing == PairHashing::Positional;
+        let (path_parameter, path_argument) = if positional {
+            ("uint256 path, ", "path, ")
+        } else {
+            ("", "")
+        };
+        let parameters = leaf
+            .parameters
+            .iter()
+            .map(|(ty, name)| format!("{} {}", ty, name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arguments = leaf
+            .parameters
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let (pairs, next_node) = if positional {
+            (
+                "hashed left to right",
+                "((path >> i) & 1) == 1\n                ? hashConcat(proof[i], node)\n                : hashConcat(node, proof[i])",
+            )
+        } else {
+            (
+                "sorted before hashing, like OpenZeppelin's MerkleProof",
+                "node < proof[i]\n                ? hashConcat(node, proof[i])\n                : hashConcat(proof[i], node)",
+            )
+        };
+        let path_doc = if positional {
+            "\n    /// @param path Bit `i` is set when `proof[i]` is the left node of its pair."
+        } else {
+            ""
+        };
+        let (claim_state, claim_function) = if options.claim {
+            (
+                CLAIM_STATE.to_string(),
+                format!(
+                    claim_template!(),
+                    path_parameter = path_parameter,
+                    path_argument = path_argument,
+                    parameters = parameters,
+                    arguments = arguments,
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+
+        Ok(format!(
+            contract_template!(),
+            name = name,
+            leaf_count = self.leaf_count(),
+            leaf_hash = leaf_hash,
+            pairs = pairs,
+            root = root,
+            claim_state = claim_state,
+            parameters = parameters,
+            path_doc = path_doc,
+            path_parameter = path_parameter,
+            path_argument = path_argument,
+            arguments = arguments,
+            claim_function = claim_function,
+            next_node = next_node,
+            hash = hash,
+        ))
+    }
+
+    fn leaf_parameters(&self) -> Result<LeafParameters, MerkleError> {
+        if !self.leaf_encoding.is_empty() {
+            let mut parameters = Vec::new();
+            for (i, ty) in self.leaf_encoding.iter().enumerate() {
+                let ty: AbiType = ty.parse()?;
+                let solidity = solidity_type(&ty).ok_or_else(|| {
+                    MerkleError::InvalidInput(format!(
+                        "The tuple type {} can't be a Solidity parameter",
+                        self.leaf_encoding[i]
+                    ))
+                })?;
+                let location = match ty {
+                    AbiType::Bytes
+                    | AbiType::String
+                    | AbiType::Array(_)
+                    | AbiType::FixedArray(..) => " calldata",
+                    _ => "",
+                };
+                parameters.push((solidity + location, format!("value{}", i)));
+            }
+            let arguments: Vec<&str> = parameters.iter().map(|(_, name)| name.as_str()).collect();
+            return Ok(LeafParameters {
+                encoded: format!("abi.encode({})", arguments.join(", ")),
+                parameters,
+            });
+        }
+
+        let mut kind = None;
+        for (index, value) in self.values.iter().enumerate() {
+            let raw = value.first().map(String::as_str).unwrap_or_default();
+            let leaf =
+                raw_leaf(raw).map_err(|e| MerkleError::LeafEncoding(index, e.to_string()))?;
+            kind = Some(match (kind, leaf) {
+                (None, leaf) => leaf,
+                (Some(previous), leaf) if previous == leaf => leaf,
+                (Some(RawLeaf::AddressAmount), _) | (_, RawLeaf::AddressAmount) => {
+                    return Err(MerkleError::LeafEncoding(
+                        index,
+                        String::from("raw `address, amount` leaves can't be mixed with hex leaves"),
+                    ))
+                }
+                _ => RawLeaf::Bytes,
+            });
+        }
+        let parameter = |ty: &str, name: &str| (ty.to_string(), name.to_string());
+        Ok(match kind.ok_or(MerkleError::EmptyInput)? {
+            RawLeaf::AddressAmount => LeafParameters {
+                parameters: vec![
+                    parameter("address", "account"),
+                    parameter("uint256", "amount"),
+                ],
+                encoded: String::from("abi.encode(account, amount)"),
+            },
+            RawLeaf::Address => LeafParameters {
+                parameters: vec![parameter("address", "account")],
+                encoded: String::from("abi.encodePacked(account)"),
+            },
+            RawLeaf::Bytes32 => LeafParameters {
+                parameters: vec![parameter("bytes32", "data")],
+                encoded: String::from("abi.encodePacked(data)"),
+            },
+            RawLeaf::Bytes => LeafParameters {
+                parameters: vec![parameter("bytes calldata", "data")],
+                encoded: String::from("data"),
+            },
+        })
+    }
+}
+
+/// Classifies a raw leaf the way [`leaf_bytes`] decodes it.
+fn raw_leaf(raw: &str) -> Result<RawLeaf, MerkleError> {
+    if raw.contains(',') {
+        return Ok(RawLeaf::AddressAmount);
+    }
+    Ok(match leaf_bytes(raw)?.len() {
+        20 => RawLeaf::Address,
+        32 => RawLeaf::Bytes32,
+        _ => RawLeaf::Bytes,
+    })
+}
+
+/// Returns the Solidity name of an ABI type, or `None` for a tuple, which would need a struct.
+fn solidity_type(ty: &AbiType) -> Option<String> {
+    Some(match ty {
+        AbiType::Address => String::from("address"),
+        AbiType::Bool => String::from("bool"),
+        AbiType::Uint(bits) => format!("uint{}", bits),
+        AbiType::Int(bits) => format!("int{}", bits),
+        AbiType::FixedBytes(size) => format!("bytes{}", size),
+        AbiType::Bytes => String::from("bytes"),
+        AbiType::String => String::from("string"),
+        AbiType::Array(inner) => format!("{}[]", solidity_type(inner)?),
+        AbiType::FixedArray(inner, len) => format!("{}[{}]", solidity_type(inner)?, len),
+        AbiType::Tuple(_) => return None,
+    })
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hasher::{Blake3, Sha256};
+
+    fn addresses(count: u8) -> Vec<String> {
+        (1..=count)
+            .map(|i| format!("0x{}", hex::encode([i; 20])))
+            .collect()
+    }
+
+    #[test]
+    fn test_raw_leaf_encodings() {
+        let options = SolidityOptions::default();
+        let tree = MerkleTree::new(&addresses(3)).unwrap();
+        let contract = tree.solidity_verifier(&options).unwrap();
+        assert!(contract.contains(&format!(
+            "bytes32 public constant ROOT = {};",
+            tree.root.as_ref().unwrap().data
+        )));
+        assert!(contract.contains("function leafHash(address account)"));
+        assert!(contract.contains("return keccak256(abi.encodePacked(account));"));
+        assert!(contract.contains("node = node < proof[i]"));
+        assert!(!contract.contains("claim"));
+
+        let pairs: Vec<String> = addresses(2).iter().map(|a| format!("{}, 100", a)).collect();
+        let contract = MerkleTree::new_standard(&pairs)
+            .unwrap()
+            .solidity_verifier(&options)
+            .unwrap();
+        assert!(contract
+            .contains("return keccak256(bytes.concat(keccak256(abi.encode(account, amount))));"));
+
+        let mixed = vec![String::from("0xabc"), String::from("0xdef")];
+        let contract = MerkleTree::new(&mixed)
+            .unwrap()
+            .solidity_verifier(&options)
+            .unwrap();
+        assert!(contract.contains("function leafHash(bytes calldata data)"));
+        assert!(contract.contains("return keccak256(data);"));
+
+        let invalid = vec![pairs[0].clone(), addresses(1)[0].clone()];
+        assert!(matches!(
+            MerkleTree::new(&invalid)
+                .unwrap()
+                .solidity_verifier(&options),
+            Err(MerkleError::LeafEncoding(1, _))
+        ));
+    }
+
+    #[test]
+    fn test_typed_positional_claim() {
+        let values: Vec<Vec<String>> = addresses(3)
+            .into_iter()
+            .map(|a| vec![a, String::from("[1,2]"), String::from("\"memo\"")])
+            .collect();
+        let options = SolidityOptions {
+            contract_name: String::from("Airdrop"),
+            claim: true,
+        };
+        let contract = MerkleTree::of(&values, &["address", "uint[]", "string"])
+            .unwrap()
+            .with_pair_hashing(PairHashing::Positional)
+            .solidity_verifier(&options)
+            .unwrap();
+        assert!(contract.contains("contract Airdrop {"));
+        assert!(contract.contains(
+            "function leafHash(address value0, uint256[] calldata value1, string calldata value2)"
+        ));
+        assert!(contract.contains("function claim(bytes32[] calldata proof, uint256 path, "));
+        assert!(contract.contains("((path >> i) & 1) == 1"));
+        assert!(contract.contains("mapping(bytes32 => bool) public claimed;"));
+    }
+
+    #[test]
+    fn test_unsupported_trees() {
+        let data = addresses(2);
+        let contract = MerkleTree::<Sha256>::new_with_hasher(&data)
+            .unwrap()
+            .solidity_verifier(&SolidityOptions::default())
+            .unwrap();
+        assert!(contract.contains("return sha256(abi.encodePacked(left, right));"));
+        assert!(MerkleTree::<Blake3>::new_with_hasher(&data)
+            .unwrap()
+            .solidity_verifier(&SolidityOptions::default())
+            .is_err());
+
+        let tree = MerkleTree::new(&data).unwrap();
+        for name in ["", "1Verifier", "My Verifier"] {
+            let options = SolidityOptions {
+                contract_name: name.to_string(),
+                claim: false,
+            };
+            assert!(tree.solidity_verifier(&options).is_err());
+        }
+        let values = vec![vec![String::from("(1,2)")]];
+        assert!(MerkleTree::of(&values, &["(uint8,uint8)"])
+            .unwrap()
+            .solidity_verifier(&SolidityOptions::default())
+            .is_err());
+    }
+}
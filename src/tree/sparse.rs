@@ -0,0 +1,322 @@
+//! # Sparse
+//!
+//! Provides a sparse Merkle Tree keyed by 256-bit keys, with proofs of inclusion and of non-inclusion.
+//!
+//! Every key has its own leaf, 256 levels below the root: the path goes left or right following the bits
+//! of the key, from the most significant one. A leaf holding `value` is `hash(key ++ value)` and an
+//! empty leaf is zero, so a proof that the leaf of a key is empty shows the key isn't in the tree.
+//!
+//! Only the nodes above set keys are stored. An empty subtree of height `h` is the default hash
+//! `defaults[h]`, with `defaults[0] = 0` and `defaults[h + 1] = hash(defaults[h] ++ defaults[h])`.
+//! Proofs leave default siblings out and mark the ones they carry in a bitmap.
+
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// The number of levels between the root and the leaves, one per bit of a key.
+pub const SPARSE_DEPTH: usize = 256;
+
+/// A sparse Merkle Tree mapping 256-bit keys to 32-byte values.
+///
+/// # Example
+///
+/// ```
+/// use eth_merkle_tree::tree::SparseMerkleTree;
+/// use eth_merkle_tree::utils::keccak::keccak256_bytes;
+/// use eth_merkle_tree::utils::verify::verify_sparse_proof;
+///
+/// let blocked = keccak256_bytes(&hex::decode("901Ab22EdCA65188686C9742F2C88c946698bc90").unwrap());
+/// let other = keccak256_bytes(&hex::decode("7b95d138cD923476b6e697391DD2aA01D15BAB27").unwrap());
+/// let mut tree = SparseMerkleTree::new();
+/// tree.insert(blocked, keccak256_bytes(b"blocked"));
+///
+/// let proof = tree.generate_proof(&other);
+/// assert!(proof.value.is_none());
+/// assert!(verify_sparse_proof(&proof, &tree.root()));
+/// ```
+pub struct SparseMerkleTree<H = Keccak256> {
+    values: HashMap<Hash32, Hash32>,
+    /// The nodes that differ from their default hash, by height and by key with the bits below the height cleared.
+    nodes: HashMap<(usize, Hash32), Hash32>,
+    defaults: Vec<Hash32>,
+    hasher: PhantomData<H>,
+}
+
+/// A proof that a key holds a value, or that it's absent, in a [`SparseMerkleTree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseProof {
+    pub key: Hash32,
+    /// The value of the key, `None` for a proof of non-inclusion.
+    pub value: Option<Hash32>,
+    /// The siblings that aren't default hashes, from the leaf up to the root.
+    pub siblings: Vec<Hash32>,
+    /// A big-endian 256-bit bitmap, bit `h` being set when the sibling at height `h` is in `siblings`.
+    pub bitmap: [u8; 32],
+}
+
+impl SparseMerkleTree {
+    /// Creates an empty tree hashing with Keccak-256.
+    pub fn new() -> Self {
+        Self::new_with_hasher()
+    }
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: MerkleHasher> SparseMerkleTree<H> {
+    /// Creates an empty tree like [`SparseMerkleTree::new`], hashing with `H` instead of Keccak-256.
+    pub fn new_with_hasher() -> Self {
+        SparseMerkleTree {
+            values: HashMap::new(),
+            nodes: HashMap::new(),
+            defaults: default_hashes::<H>(),
+            hasher: PhantomData,
+        }
+    }
+
+    /// Returns the root, the default hash of the full depth while the tree is empty.
+    pub fn root(&self) -> Hash32 {
+        self.node(SPARSE_DEPTH, &Hash32::default())
+    }
+
+    /// Returns the number of keys holding a value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no key holds a value.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value of `key`, if any.
+    pub fn get(&self, key: &Hash32) -> Option<Hash32> {
+        self.values.get(key).copied()
+    }
+
+    /// Sets the value of `key`, rehashing the 256 nodes on its path.
+    ///
+    /// # Returns
+    ///
+    /// - The previous value of the key, if any.
+    pub fn insert(&mut self, key: Hash32, value: Hash32) -> Option<Hash32> {
+        let previous = self.values.insert(key, value);
+        self.update_path(&key, leaf_node::<H>(&key, &value));
+        previous
+    }
+
+    /// Removes the value of `key`, restoring the default hashes on its path.
+    ///
+    /// # Returns
+    ///
+    /// - The removed value, or `None` if the key held no value.
+    pub fn delete(&mut self, key: &Hash32) -> Option<Hash32> {
+        let previous = self.values.remove(key)?;
+        self.update_path(key, self.defaults[0]);
+        Some(previous)
+    }
+
+    /// Generates a proof for `key`: of inclusion if it holds a value, of non-inclusion otherwise.
+    ///
+    /// The proof can be checked with [`crate::utils::verify::verify_sparse_proof`].
+    pub fn generate_proof(&self, key: &Hash32) -> SparseProof {
+        let mut siblings = Vec::new();
+        let mut bitmap = [0u8; 32];
+        for height in 0..SPARSE_DEPTH {
+            let sibling = flip_bit(&clear_low_bits(key, height), height);
+            if let Some(node) = self.nodes.get(&(height, sibling)) {
+                siblings.push(*node);
+                set_bit(&mut bitmap, height);
+            }
+        }
+        SparseProof {
+            key: *key,
+            value: self.get(key),
+            siblings,
+            bitmap,
+        }
+    }
+
+    fn node(&self, height: usize, prefix: &Hash32) -> Hash32 {
+        self.nodes
+            .get(&(height, *prefix))
+            .copied()
+            .unwrap_or(self.defaults[height])
+    }
+
+    fn update_path(&mut self, key: &Hash32, leaf: Hash32) {
+        let mut node = leaf;
+        for height in 0..=SPARSE_DEPTH {
+            let prefix = clear_low_bits(key, height);
+            if node == self.defaults[height] {
+                self.nodes.remove(&(height, prefix));
+            } else {
+                self.nodes.insert((height, prefix), node);
+            }
+            if height == SPARSE_DEPTH {
+                break;
+            }
+            let sibling = self.node(height, &flip_bit(&prefix, height));
+            node = if bit(key, height) {
+                H::hash_concat(&sibling, &node)
+            } else {
+                H::hash_concat(&node, &sibling)
+            };
+        }
+    }
+}
+
+impl SparseProof {
+    /// Computes the root this proof leads to with the hash function `H`.
+    ///
+    /// # Returns
+    ///
+    /// - `None` if the number of siblings doesn't match the bitmap.
+    pub fn compute_root<H: MerkleHasher>(&self) -> Option<Hash32> {
+        let defaults = default_hashes::<H>();
+        let mut node = match &self.value {
+            Some(value) => leaf_node::<H>(&self.key, value),
+            None => defaults[0],
+        };
+        let mut siblings = self.siblings.iter();
+        for (height, default) in defaults.iter().enumerate().take(SPARSE_DEPTH) {
+            let sibling = if bit(&Hash32(self.bitmap), height) {
+                *siblings.next()?
+            } else {
+                *default
+            };
+            node = if bit(&self.key, height) {
+                H::hash_concat(&sibling, &node)
+            } else {
+                H::hash_concat(&node, &sibling)
+            };
+        }
+        siblings.next().is_none().then_some(node)
+    }
+}
+
+/// Returns the hash of an empty subtree of every height, from an empty leaf up to an empty tree.
+fn default_hashes<H: MerkleHasher>() -> Vec<Hash32> {
+    let mut defaults = Vec::with_capacity(SPARSE_DEPTH + 1);
+    defaults.push(Hash32::default());
+    for height in 0..SPARSE_DEPTH {
+        defaults.push(H::hash_concat(&defaults[height], &defaults[height]));
+    }
+    defaults
+}
+
+fn leaf_node<H: MerkleHasher>(key: &Hash32, value: &Hash32) -> Hash32 {
+    H::hash_concat(key, value)
+}
+
+/// Returns bit `index` of a big-endian 256-bit word, `0` being the least significant bit.
+fn bit(word: &Hash32, index: usize) -> bool {
+    word.0[31 - index / 8] >> (index % 8) & 1 == 1
+}
+
+fn set_bit(word: &mut [u8; 32], index: usize) {
+    word[31 - index / 8] |= 1 << (index % 8);
+}
+
+fn flip_bit(word: &Hash32, index: usize) -> Hash32 {
+    let mut flipped = *word;
+    flipped.0[31 - index / 8] ^= 1 << (index % 8);
+    flipped
+}
+
+/// Clears the `count` least significant bits of a big-endian 256-bit word.
+fn clear_low_bits(word: &Hash32, count: usize) -> Hash32 {
+    let mut cleared = *word;
+    for (i, byte) in cleared.0.iter_mut().rev().enumerate() {
+        let low = count.saturating_sub(8 * i).min(8);
+        *byte &= (0xffu16 << low) as u8;
+    }
+    cleared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::keccak::keccak256_bytes;
+
+    fn key(seed: u8) -> Hash32 {
+        keccak256_bytes(&[seed])
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut word = Hash32::default();
+        word.0[31] = 0b101;
+        word.0[0] = 0x80;
+        assert!(bit(&word, 0) && !bit(&word, 1) && bit(&word, 2) && bit(&word, 255));
+        assert_eq!(clear_low_bits(&word, 1).0[31], 0b100);
+        let mut top = Hash32::default();
+        top.0[0] = 0x80;
+        assert_eq!(clear_low_bits(&word, 255), top);
+        assert_eq!(clear_low_bits(&word, 256), Hash32::default());
+        let mut flag = [0u8; 32];
+        set_bit(&mut flag, 255);
+        assert_eq!(flag[0], 0x80);
+        assert_eq!(flip_bit(&word, 255).0[0], 0);
+    }
+
+    #[test]
+    fn test_insert_get_delete() {
+        let mut tree = SparseMerkleTree::new();
+        let empty_root = tree.root();
+        assert_eq!(tree.insert(key(1), key(10)), None);
+        let one_root = tree.root();
+        assert_ne!(one_root, empty_root);
+        assert_eq!(tree.insert(key(2), key(20)), None);
+        assert_eq!(tree.insert(key(2), key(21)), Some(key(20)));
+        assert_eq!(tree.get(&key(2)), Some(key(21)));
+        assert_eq!(tree.len(), 2);
+
+        assert_eq!(tree.delete(&key(2)), Some(key(21)));
+        assert_eq!(tree.delete(&key(2)), None);
+        assert_eq!(tree.root(), one_root);
+        tree.delete(&key(1));
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+    }
+
+    #[test]
+    fn test_root_is_independent_of_order() {
+        let mut forward = SparseMerkleTree::new();
+        let mut backward = SparseMerkleTree::new();
+        for i in 0..20 {
+            forward.insert(key(i), key(i + 100));
+            backward.insert(key(19 - i), key(119 - i));
+        }
+        assert_eq!(forward.root(), backward.root());
+    }
+
+    #[test]
+    fn test_proofs() {
+        let mut tree = SparseMerkleTree::new();
+        for i in 0..10 {
+            tree.insert(key(i), key(i + 100));
+        }
+        let root = tree.root();
+        let proof = tree.generate_proof(&key(3));
+        assert_eq!(proof.value, Some(key(103)));
+        assert_eq!(proof.compute_root::<Keccak256>(), Some(root));
+
+        let absent = tree.generate_proof(&key(42));
+        assert_eq!(absent.value, None);
+        assert_eq!(absent.compute_root::<Keccak256>(), Some(root));
+
+        let mut forged = absent.clone();
+        forged.key = key(3);
+        assert_ne!(forged.compute_root::<Keccak256>(), Some(root));
+        let mut truncated = proof;
+        truncated.siblings.pop();
+        assert_eq!(truncated.compute_root::<Keccak256>(), None);
+    }
+}
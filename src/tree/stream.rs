@@ -0,0 +1,396 @@
+//! # Stream
+//!
+//! Provides the construction of a Merkle Tree from a stream of leaves, without holding them in memory.
+//!
+//! [`StreamingBuilder`] computes the same root as [`MerkleTree::new`](super::MerkleTree::new), the sequential
+//! layout, keeping a single pending node per level. It can spill every level to a directory as it goes,
+//! one `level-<n>.bin` file of raw 32-byte nodes per level, and [`SpilledTree`] later reads proofs from
+//! those files by seeking to the few nodes each proof needs.
+
+use super::{DirectedProof, PairHashing, Side};
+use crate::utils::errors::MerkleError;
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// Builds the root of a sequential Merkle Tree from leaves pushed one at a time.
+///
+/// # Example
+///
+/// ```
+/// use eth_merkle_tree::tree::{MerkleTree, StreamingBuilder};
+///
+/// let data = vec![String::from("0xabc"), String::from("0xdef"), String::from("0x123")];
+/// let mut builder = StreamingBuilder::new();
+/// builder.read_lines(data.join("\n").as_bytes()).expect("Invalid leaf.");
+/// let root = builder.finish().expect("No leaves.");
+/// assert_eq!(root, MerkleTree::new(&data).unwrap().root.unwrap().data);
+/// ```
+pub struct StreamingBuilder<H = Keccak256> {
+    /// The left node of each level waiting for its right sibling.
+    pending: Vec<Option<Hash32>>,
+    leaf_count: usize,
+    pair_hashing: PairHashing,
+    spill: Option<Spill>,
+    hasher: PhantomData<H>,
+}
+
+/// The level files being written to a spill directory.
+struct Spill {
+    dir: PathBuf,
+    writers: Vec<BufWriter<File>>,
+}
+
+impl StreamingBuilder {
+    /// Creates a builder hashing leaves and pairs like [`MerkleTree::new`](super::MerkleTree::new).
+    pub fn new() -> Self {
+        Self::new_with_hasher()
+    }
+}
+
+impl Default for StreamingBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: MerkleHasher> StreamingBuilder<H> {
+    /// Creates a builder like [`StreamingBuilder::new`], hashing with `H` instead of Keccak-256.
+    pub fn new_with_hasher() -> Self {
+        StreamingBuilder {
+            pending: Vec::new(),
+            leaf_count: 0,
+            pair_hashing: PairHashing::Sorted,
+            spill: None,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Hashes pairs as given, see [`MerkleTree::with_pair_hashing`](super::MerkleTree::with_pair_hashing).
+    pub fn with_pair_hashing(mut self, pair_hashing: PairHashing) -> Self {
+        self.pair_hashing = pair_hashing;
+        self
+    }
+
+    /// Writes every level to `dir`, creating it if needed, so proofs can be read with [`SpilledTree`].
+    ///
+    /// # Errors
+    ///
+    /// - When the directory can't be created.
+    pub fn with_spill(mut self, dir: impl AsRef<Path>) -> Result<Self, MerkleError> {
+        fs::create_dir_all(dir.as_ref())?;
+        self.spill = Some(Spill {
+            dir: dir.as_ref().to_path_buf(),
+            writers: Vec::new(),
+        });
+        Ok(self)
+    }
+
+    /// Returns the number of leaves pushed so far.
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Pushes a leaf hash.
+    ///
+    /// # Errors
+    ///
+    /// - When a spilled level can't be written.
+    pub fn push_hash(&mut self, leaf: Hash32) -> Result<(), MerkleError> {
+        self.leaf_count += 1;
+        let mut node = leaf;
+        let mut level = 0;
+        loop {
+            self.spill_node(level, &node)?;
+            if level == self.pending.len() {
+                self.pending.push(None);
+            }
+            match self.pending[level].take() {
+                Some(left) => {
+                    node = self.hash_children(&left, &node);
+                    level += 1;
+                }
+                None => {
+                    self.pending[level] = Some(node);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Pushes a raw leaf, hashed like the leaves of [`MerkleTree::new`](super::MerkleTree::new).
+    ///
+    /// # Errors
+    ///
+    /// - When the leaf can't be decoded, reported with its index.
+    /// - When a spilled level can't be written.
+    pub fn push_value(&mut self, value: &str) -> Result<(), MerkleError> {
+        let leaf = H::leaf_hash(value)
+            .map_err(|e| MerkleError::LeafEncoding(self.leaf_count, e.to_string()))?;
+        self.push_hash(leaf)
+    }
+
+    /// Pushes every raw leaf of an iterator.
+    ///
+    /// # Errors
+    ///
+    /// - When a leaf can't be decoded, reported with its index.
+    /// - When a spilled level can't be written.
+    pub fn extend<S: AsRef<str>>(
+        &mut self,
+        values: impl IntoIterator<Item = S>,
+    ) -> Result<(), MerkleError> {
+        values
+            .into_iter()
+            .try_for_each(|value| self.push_value(value.as_ref()))
+    }
+
+    /// Pushes one raw leaf per non-empty line of `reader`, like the leaf files of the CLI.
+    ///
+    /// # Errors
+    ///
+    /// - When the reader fails, or a line isn't valid UTF-8.
+    /// - When a leaf can't be decoded, reported with its index.
+    /// - When a spilled level can't be written.
+    pub fn read_lines(&mut self, reader: impl Read) -> Result<(), MerkleError> {
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                self.push_value(&line)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the root, hashing the odd nodes left on each level the way the sequential layout does.
+    ///
+    /// # Errors
+    ///
+    /// - When no leaf was pushed.
+    /// - When a spilled level can't be written.
+    pub fn finish(mut self) -> Result<Hash32, MerkleError> {
+        if self.leaf_count == 0 {
+            return Err(MerkleError::EmptyInput);
+        }
+        // An odd last node is promoted to the next level, where it's the right sibling of the pending node.
+        let mut carry: Option<Hash32> = None;
+        let mut root_level = 0;
+        for level in 0..self.pending.len() {
+            if let Some(node) = carry {
+                self.spill_node(level, &node)?;
+            }
+            carry = match (self.pending[level], carry) {
+                (Some(left), Some(right)) => {
+                    root_level = level + 1;
+                    Some(self.hash_children(&left, &right))
+                }
+                (left, right) => {
+                    root_level = level;
+                    left.or(right)
+                }
+            };
+        }
+        let root = carry.expect("At least one leaf");
+        if root_level == self.pending.len() {
+            self.spill_node(root_level, &root)?;
+        }
+        if let Some(spill) = self.spill.as_mut() {
+            for writer in &mut spill.writers {
+                writer.flush()?;
+            }
+            let mut level = spill.writers.len();
+            while fs::remove_file(level_path(&spill.dir, level)).is_ok() {
+                level += 1;
+            }
+        }
+        Ok(root)
+    }
+
+    fn hash_children(&self, left: &Hash32, right: &Hash32) -> Hash32 {
+        match self.pair_hashing {
+            PairHashing::Sorted => H::hash_pair(left, right),
+            PairHashing::Positional => H::hash_concat(left, right),
+        }
+    }
+
+    fn spill_node(&mut self, level: usize, node: &Hash32) -> Result<(), MerkleError> {
+        if let Some(spill) = self.spill.as_mut() {
+            while spill.writers.len() <= level {
+                let path = level_path(&spill.dir, spill.writers.len());
+                spill.writers.push(BufWriter::new(File::create(path)?));
+            }
+            spill.writers[level].write_all(node.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// The levels of a tree spilled by a [`StreamingBuilder`], read from disk on demand.
+pub struct SpilledTree {
+    dir: PathBuf,
+    /// The number of nodes of each level, from the leaves up to the root.
+    level_lens: Vec<usize>,
+}
+
+impl SpilledTree {
+    /// Opens the levels spilled to `dir`.
+    ///
+    /// # Errors
+    ///
+    /// - When a level file can't be read.
+    /// - When the levels don't form a complete tree, such as when the builder wasn't finished.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, MerkleError> {
+        let dir = dir.as_ref().to_path_buf();
+        let mut level_lens = Vec::new();
+        while let Ok(metadata) = fs::metadata(level_path(&dir, level_lens.len())) {
+            level_lens.push(metadata.len() as usize / 32);
+        }
+        let complete = level_lens.last() == Some(&1)
+            && level_lens
+                .windows(2)
+                .all(|lens| lens[1] == lens[0].div_ceil(2) && lens[0] > 1);
+        if !complete {
+            return Err(MerkleError::InvalidInput(format!(
+                "{} doesn't hold the levels of a finished tree",
+                dir.display()
+            )));
+        }
+        Ok(SpilledTree { dir, level_lens })
+    }
+
+    /// Returns the number of leaves of the tree.
+    pub fn leaf_count(&self) -> usize {
+        self.level_lens[0]
+    }
+
+    /// Returns the root of the tree.
+    ///
+    /// # Errors
+    ///
+    /// - When the top level can't be read.
+    pub fn root(&self) -> Result<Hash32, MerkleError> {
+        self.read_node(self.level_lens.len() - 1, 0)
+    }
+
+    /// Returns the leaf hash at `index`.
+    ///
+    /// # Errors
+    ///
+    /// - When `index` is out of bounds, or the leaves can't be read.
+    pub fn leaf(&self, index: usize) -> Result<Hash32, MerkleError> {
+        if index >= self.leaf_count() {
+            return Err(MerkleError::InvalidIndex(index));
+        }
+        self.read_node(0, index)
+    }
+
+    /// Generates the proof of the leaf at `index`, the same one [`MerkleTree::generate_proof`](super::MerkleTree::generate_proof)
+    /// returns for a tree built from the same leaves.
+    ///
+    /// # Errors
+    ///
+    /// - When `index` is out of bounds, or a level can't be read.
+    pub fn generate_proof(&self, index: usize) -> Result<Vec<Hash32>, MerkleError> {
+        Ok(self.generate_directed_proof(index)?.siblings)
+    }
+
+    /// Generates the proof of the leaf at `index` with the side of each sibling.
+    ///
+    /// # Errors
+    ///
+    /// - When `index` is out of bounds, or a level can't be read.
+    pub fn generate_directed_proof(&self, index: usize) -> Result<DirectedProof, MerkleError> {
+        if index >= self.leaf_count() {
+            return Err(MerkleError::InvalidIndex(index));
+        }
+        let mut siblings = Vec::new();
+        let mut sides = Vec::new();
+        let mut position = index;
+        for (level, len) in self.level_lens.iter().enumerate() {
+            let sibling = position ^ 1;
+            if sibling < *len {
+                siblings.push(self.read_node(level, sibling)?);
+                sides.push(if sibling < position {
+                    Side::Left
+                } else {
+                    Side::Right
+                });
+            }
+            position /= 2;
+        }
+        Ok(DirectedProof { siblings, sides })
+    }
+
+    fn read_node(&self, level: usize, index: usize) -> Result<Hash32, MerkleError> {
+        let mut file = File::open(level_path(&self.dir, level))?;
+        file.seek(SeekFrom::Start(32 * index as u64))?;
+        let mut node = [0u8; 32];
+        file.read_exact(&mut node)?;
+        Ok(Hash32(node))
+    }
+}
+
+fn level_path(dir: &Path, level: usize) -> PathBuf {
+    dir.join(format!("level-{}.bin", level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{reference_tree, test_values};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("eth-merkle-tree-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_root_matches_tree() {
+        for count in 1..=33 {
+            let data = test_values(count);
+            for pair_hashing in [PairHashing::Sorted, PairHashing::Positional] {
+                let mut builder = StreamingBuilder::new().with_pair_hashing(pair_hashing);
+                builder.extend(&data).expect("Invalid leaf");
+                assert_eq!(builder.leaf_count(), count);
+                let tree = reference_tree(&data, pair_hashing);
+                assert_eq!(builder.finish().unwrap(), tree.root.unwrap().data);
+            }
+        }
+        assert!(matches!(
+            StreamingBuilder::new().finish(),
+            Err(MerkleError::EmptyInput)
+        ));
+        let mut builder = StreamingBuilder::new();
+        assert!(matches!(
+            builder.read_lines("0xabc\n\n0xxyz\n".as_bytes()),
+            Err(MerkleError::LeafEncoding(1, _))
+        ));
+    }
+
+    #[test]
+    fn test_spilled_proofs_match_tree() {
+        let dir = temp_dir("spill");
+        for count in [1, 2, 5, 8, 13] {
+            let data = test_values(count);
+            let mut builder = StreamingBuilder::new().with_spill(&dir).unwrap();
+            builder.extend(&data).unwrap();
+            let root = builder.finish().unwrap();
+
+            let spilled = SpilledTree::open(&dir).expect("Invalid spill");
+            let tree = reference_tree(&data, PairHashing::Sorted);
+            assert_eq!(spilled.leaf_count(), count);
+            assert_eq!(spilled.root().unwrap(), root);
+            for index in 0..count {
+                assert_eq!(
+                    spilled.generate_directed_proof(index).unwrap(),
+                    tree.generate_directed_proof(index).unwrap()
+                );
+            }
+            assert!(spilled.generate_proof(count).is_err());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(SpilledTree::open(&dir).is_err());
+    }
+}
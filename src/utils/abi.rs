@@ -0,0 +1,811 @@
+//! # Abi
+//!
+//! Provides Solidity ABI encoding of typed leaf values, following both `abi.encode` and `abi.encodePacked` semantics.
+//!
+//! Types are given as Solidity type strings such as `address`, `uint256`, `bytes32`, `string`, `bool`,
+//! `uint8[3]`, `address[]` or `(address,uint256)`. Values are given as strings:
+//!
+//! * `address`, `bytes` and `bytesN` values are `0x`-prefixed hex.
+//! * `uintN` and `intN` values are decimal, or `0x`-prefixed hex.
+//! * `bool` values are `true` or `false`.
+//! * `string` values are taken verbatim, surrounding whitespace, quotes and brackets included, as `abi.encode`
+//!   does. Inside arrays and tuples, they may be wrapped in double quotes to hold commas or brackets.
+//! * Array values are written as `[a, b, c]` and tuple values as `(a, b)` or `[a, b]`.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::utils::abi::encode;
+//! let encoded = encode(
+//!     &["address", "uint256"],
+//!     &["0x5B38Da6a701c568545dCfcB03FcB875f56beddC4", "100"],
+//! )
+//! .expect("Failed to encode values");
+//! assert_eq!(encoded.len(), 64);
+//! ```
+
+use crate::utils::errors::{AbiError, MerkleError};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::str::FromStr;
+
+/// A Solidity ABI type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Address,
+    Bool,
+    /// `uintN`, holding the bit size `N`.
+    Uint(usize),
+    /// `intN`, holding the bit size `N`.
+    Int(usize),
+    /// `bytesN`, holding the byte size `N`.
+    FixedBytes(usize),
+    Bytes,
+    String,
+    /// `T[]`
+    Array(Box<AbiType>),
+    /// `T[k]`
+    FixedArray(Box<AbiType>, usize),
+    /// `(T1,T2,...)`
+    Tuple(Vec<AbiType>),
+}
+
+impl AbiType {
+    /// Returns `true` if the type is dynamically sized in the ABI encoding.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Bytes | AbiType::String | AbiType::Array(_) => true,
+            AbiType::FixedArray(inner, _) => inner.is_dynamic(),
+            AbiType::Tuple(types) => types.iter().any(AbiType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Returns the size of the head of the type in the ABI encoding.
+    fn head_size(&self) -> usize {
+        match self {
+            AbiType::FixedArray(inner, len) if !self.is_dynamic() => inner.head_size() * len,
+            AbiType::Tuple(types) if !self.is_dynamic() => {
+                types.iter().map(AbiType::head_size).sum()
+            }
+            _ => 32,
+        }
+    }
+}
+
+impl FromStr for AbiType {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ty = s.trim();
+        let invalid = || AbiError::InvalidType(ty.to_string());
+
+        if let Some(stripped) = ty.strip_suffix(']') {
+            let open = stripped.rfind('[').ok_or_else(invalid)?;
+            let inner = Box::new(stripped[..open].parse::<AbiType>()?);
+            let size = &stripped[open + 1..];
+            return if size.is_empty() {
+                Ok(AbiType::Array(inner))
+            } else {
+                let len = size.parse::<usize>().map_err(|_| invalid())?;
+                Ok(AbiType::FixedArray(inner, len))
+            };
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            let types = split_top_level(inner)
+                .map_err(|_| invalid())?
+                .iter()
+                .map(|t| t.parse::<AbiType>())
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(AbiType::Tuple(types));
+        }
+
+        match ty {
+            "address" => return Ok(AbiType::Address),
+            "bool" => return Ok(AbiType::Bool),
+            "bytes" => return Ok(AbiType::Bytes),
+            "string" => return Ok(AbiType::String),
+            "uint" => return Ok(AbiType::Uint(256)),
+            "int" => return Ok(AbiType::Int(256)),
+            "byte" => return Ok(AbiType::FixedBytes(1)),
+            _ => {}
+        }
+        let sized = |prefix: &str| -> Option<usize> { ty.strip_prefix(prefix)?.parse().ok() };
+        if let Some(bits) = sized("uint") {
+            if bits > 0 && bits <= 256 && bits % 8 == 0 {
+                return Ok(AbiType::Uint(bits));
+            }
+        } else if let Some(bits) = sized("int") {
+            if bits > 0 && bits <= 256 && bits % 8 == 0 {
+                return Ok(AbiType::Int(bits));
+            }
+        } else if let Some(size) = sized("bytes") {
+            if size > 0 && size <= 32 {
+                return Ok(AbiType::FixedBytes(size));
+            }
+        }
+        Err(invalid())
+    }
+}
+
+/// Encodes the values following `abi.encode(...)`.
+///
+/// # Arguments
+///
+/// * `types` - The Solidity type of each value.
+/// * `values` - The values to encode.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the encoded bytes, or a `MerkleError::Abi` on failure.
+///
+/// # Errors
+///
+/// - When the number of types and values differ.
+/// - When a type or value can't be parsed.
+///
+pub fn encode<T: AsRef<str>, V: AsRef<str>>(
+    types: &[T],
+    values: &[V],
+) -> Result<Vec<u8>, MerkleError> {
+    let (types, values) = parse_arguments(types, values)?;
+    Ok(encode_sequence(&types, &values)?)
+}
+
+/// Encodes the values following `abi.encodePacked(...)`.
+///
+/// Static values use their minimal size, `bytes` and `string` are not length-prefixed and array elements are
+/// padded to 32 bytes, as Solidity does.
+///
+/// # Arguments
+///
+/// * `types` - The Solidity type of each value.
+/// * `values` - The values to encode.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the encoded bytes, or a `MerkleError::Abi` on failure.
+///
+/// # Errors
+///
+/// - When the number of types and values differ.
+/// - When a type or value can't be parsed.
+/// - When a tuple or an array of dynamic types is given, which Solidity can't pack.
+///
+pub fn encode_packed<T: AsRef<str>, V: AsRef<str>>(
+    types: &[T],
+    values: &[V],
+) -> Result<Vec<u8>, MerkleError> {
+    let (types, values) = parse_arguments(types, values)?;
+    Ok(pack_values(&types, &values)?)
+}
+
+fn pack_values(types: &[AbiType], values: &[Literal]) -> Result<Vec<u8>, AbiError> {
+    let mut packed = Vec::new();
+    for (ty, value) in types.iter().zip(values) {
+        match ty {
+            AbiType::Tuple(_) => return Err(AbiError::UnsupportedPacked(format!("{:?}", ty))),
+            AbiType::Array(inner) | AbiType::FixedArray(inner, _) => {
+                if inner.is_dynamic() || matches!(**inner, AbiType::Tuple(_)) {
+                    return Err(AbiError::UnsupportedPacked(format!("{:?}", ty)));
+                }
+                for item in expect_list(ty, value, array_len(ty))? {
+                    packed.extend(encode_value(inner, item)?);
+                }
+            }
+            AbiType::Bytes => packed.extend(parse_hex(ty, expect_scalar(ty, value)?)?),
+            AbiType::String => packed.extend(expect_scalar(ty, value)?.as_bytes()),
+            _ => {
+                let word = encode_value(ty, value)?;
+                let size = packed_size(ty);
+                match ty {
+                    AbiType::FixedBytes(_) => packed.extend(&word[..size]),
+                    _ => packed.extend(&word[32 - size..]),
+                }
+            }
+        }
+    }
+    Ok(packed)
+}
+
+/// Splits a comma-separated list of values, keeping arrays, tuples and quoted strings whole.
+///
+/// This turns a leaf written as `0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, [1, 2]` into the values
+/// expected by [`encode`]. Values are trimmed, and a value wrapped in double quotes loses its quotes, so
+/// `"a, b"` is the string `a, b`.
+///
+/// # Errors
+///
+/// - When brackets or quotes aren't balanced.
+///
+pub fn split_values(input: &str) -> Result<Vec<String>, MerkleError> {
+    let parts = split_top_level(input)
+        .map_err(|_| AbiError::InvalidValue(String::from("list"), input.to_string()))?;
+    Ok(parts
+        .into_iter()
+        .map(|part| unquote(part).into_owned())
+        .collect())
+}
+
+/// Converts a JSON value, such as a value of a tree dump or a field of a JSON leaf file, to the value syntax above.
+///
+/// Strings are kept verbatim, numbers and booleans are written out and arrays become `[a, b]` lists.
+///
+/// # Errors
+///
+/// - When the value is `null` or an object.
+///
+pub fn value_from_json(value: &Value) -> Result<String, MerkleError> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Array(items) => Ok(format!(
+            "[{}]",
+            items
+                .iter()
+                .map(nested_value_from_json)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ")
+        )),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(AbiError::InvalidValue(String::from("json"), value.to_string()).into()),
+    }
+}
+
+/// Like `value_from_json`, but quotes strings that would otherwise be split inside a list.
+fn nested_value_from_json(value: &Value) -> Result<String, MerkleError> {
+    match value {
+        Value::String(s) if s.contains([',', '[', ']', '(', ')', '"', '\\']) => Ok(format!(
+            "\"{}\"",
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        )),
+        _ => value_from_json(value),
+    }
+}
+
+/// Parses an unsigned integer of up to 256 bits, given in decimal or as `0x`-prefixed hex.
+///
+/// # Returns
+///
+/// Returns the value as a big-endian 32-byte word, or `None` if it isn't a valid number or overflows.
+///
+pub fn parse_uint256(value: &str) -> Option<[u8; 32]> {
+    let value = value.trim();
+    let mut word = [0u8; 32];
+    if let Some(hex_digits) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        let digits = hex_digits.trim_start_matches('0');
+        if hex_digits.is_empty() || digits.len() > 64 {
+            return None;
+        }
+        let padded = format!("{:0>64}", digits);
+        hex::decode_to_slice(padded, &mut word).ok()?;
+        return Some(word);
+    }
+    if value.is_empty() {
+        return None;
+    }
+    for digit in value.chars() {
+        let mut carry = digit.to_digit(10)?;
+        for byte in word.iter_mut().rev() {
+            let product = *byte as u32 * 10 + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(word)
+}
+
+/// A value literal, split into nested lists but not yet interpreted.
+#[derive(Debug)]
+pub(crate) enum Literal<'a> {
+    Scalar(Cow<'a, str>),
+    List(Vec<Literal<'a>>),
+}
+
+fn parse_arguments<'a, T: AsRef<str>, V: AsRef<str>>(
+    types: &[T],
+    values: &'a [V],
+) -> Result<(Vec<AbiType>, Vec<Literal<'a>>), AbiError> {
+    if types.len() != values.len() {
+        return Err(AbiError::LengthMismatch(types.len(), values.len()));
+    }
+    let types = types
+        .iter()
+        .map(|t| t.as_ref().parse::<AbiType>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let values = types
+        .iter()
+        .zip(values)
+        .map(|(ty, v)| {
+            parse_value(ty, v.as_ref())
+                .map_err(|_| AbiError::InvalidValue(format!("{:?}", ty), v.as_ref().to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((types, values))
+}
+
+/// Parses a value of type `ty`. `string` values are taken verbatim, anything else is parsed as a literal.
+pub(crate) fn parse_value<'a>(ty: &AbiType, value: &'a str) -> Result<Literal<'a>, ()> {
+    match ty {
+        AbiType::String => Ok(Literal::Scalar(Cow::Borrowed(value))),
+        _ => parse_literal(value),
+    }
+}
+
+/// Splits `[a, b]` and `(a, b)` into lists and unquotes `"a"`. Anything else is kept as a trimmed scalar.
+fn parse_literal(value: &str) -> Result<Literal<'_>, ()> {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .or_else(|| value.strip_prefix('(').and_then(|v| v.strip_suffix(')')));
+    match inner {
+        Some(inner) if inner.trim().is_empty() => Ok(Literal::List(Vec::new())),
+        Some(inner) => Ok(Literal::List(
+            split_top_level(inner)?
+                .into_iter()
+                .map(parse_literal)
+                .collect::<Result<_, _>>()?,
+        )),
+        None => Ok(Literal::Scalar(unquote(value))),
+    }
+}
+
+/// Splits on commas that aren't nested in brackets or double quotes.
+fn split_top_level(s: &str) -> Result<Vec<&str>, ()> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if in_quotes {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.checked_sub(1).ok_or(())?,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 || in_quotes {
+        return Err(());
+    }
+    parts.push(s[start..].trim());
+    Ok(parts)
+}
+
+fn invalid_value(ty: &AbiType, value: &Literal) -> AbiError {
+    AbiError::InvalidValue(format!("{:?}", ty), format!("{:?}", value))
+}
+
+fn expect_scalar<'l>(ty: &AbiType, value: &'l Literal) -> Result<&'l str, AbiError> {
+    match value {
+        Literal::Scalar(s) => Ok(s),
+        Literal::List(_) => Err(invalid_value(ty, value)),
+    }
+}
+
+fn expect_list<'l, 'a>(
+    ty: &AbiType,
+    value: &'l Literal<'a>,
+    len: Option<usize>,
+) -> Result<&'l [Literal<'a>], AbiError> {
+    match value {
+        Literal::List(items) if len.is_none_or(|len| len == items.len()) => Ok(items),
+        _ => Err(invalid_value(ty, value)),
+    }
+}
+
+fn array_len(ty: &AbiType) -> Option<usize> {
+    match ty {
+        AbiType::FixedArray(_, len) => Some(*len),
+        AbiType::Tuple(types) => Some(types.len()),
+        _ => None,
+    }
+}
+
+fn packed_size(ty: &AbiType) -> usize {
+    match ty {
+        AbiType::Address => 20,
+        AbiType::Bool => 1,
+        AbiType::Uint(bits) | AbiType::Int(bits) => bits / 8,
+        AbiType::FixedBytes(size) => *size,
+        _ => 32,
+    }
+}
+
+fn parse_hex(ty: &AbiType, value: &str) -> Result<Vec<u8>, AbiError> {
+    value
+        .strip_prefix("0x")
+        .and_then(|digits| hex::decode(digits).ok())
+        .ok_or_else(|| AbiError::InvalidValue(format!("{:?}", ty), value.to_string()))
+}
+
+/// Removes the double quotes around a value and unescapes the quotes and backslashes inside.
+fn unquote(value: &str) -> Cow<'_, str> {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => Cow::Owned(inner.replace("\\\"", "\"").replace("\\\\", "\\")),
+        None => Cow::Borrowed(value),
+    }
+}
+
+/// Encodes values as the members of a tuple: the heads first, followed by the tails of the dynamic values.
+fn encode_sequence(types: &[AbiType], values: &[Literal]) -> Result<Vec<u8>, AbiError> {
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    let head_len: usize = types.iter().map(AbiType::head_size).sum();
+    for (ty, value) in types.iter().zip(values) {
+        let encoded = encode_value(ty, value)?;
+        if ty.is_dynamic() {
+            head.extend(uint_word(head_len + tail.len()));
+            tail.extend(encoded);
+        } else {
+            head.extend(encoded);
+        }
+    }
+    head.extend(tail);
+    Ok(head)
+}
+
+fn encode_value(ty: &AbiType, value: &Literal) -> Result<Vec<u8>, AbiError> {
+    let invalid = || invalid_value(ty, value);
+    match ty {
+        AbiType::Address => {
+            let bytes = parse_hex(ty, expect_scalar(ty, value)?)?;
+            if bytes.len() != 20 {
+                return Err(invalid());
+            }
+            let mut word = vec![0u8; 32];
+            word[12..].copy_from_slice(&bytes);
+            Ok(word)
+        }
+        AbiType::Bool => match expect_scalar(ty, value)? {
+            "true" => Ok(uint_word(1)),
+            "false" => Ok(uint_word(0)),
+            _ => Err(invalid()),
+        },
+        AbiType::Uint(bits) => {
+            let word = parse_uint256(expect_scalar(ty, value)?).ok_or_else(invalid)?;
+            if !fits_in_bits(&word, *bits) {
+                return Err(invalid());
+            }
+            Ok(word.to_vec())
+        }
+        AbiType::Int(bits) => {
+            let scalar = expect_scalar(ty, value)?;
+            let (negative, magnitude) = match scalar.strip_prefix('-') {
+                Some(m) => (true, m),
+                None => (false, scalar),
+            };
+            let word = parse_uint256(magnitude).ok_or_else(invalid)?;
+            // The magnitude may reach 2^(bits - 1) only for negative values.
+            let in_range =
+                fits_in_bits(&word, bits - 1) || (negative && word == min_magnitude(*bits));
+            if !in_range {
+                return Err(invalid());
+            }
+            Ok(if negative { negate(word) } else { word }.to_vec())
+        }
+        AbiType::FixedBytes(size) => {
+            let bytes = parse_hex(ty, expect_scalar(ty, value)?)?;
+            if bytes.len() != *size {
+                return Err(invalid());
+            }
+            let mut word = vec![0u8; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(word)
+        }
+        AbiType::Bytes => Ok(encode_dynamic_bytes(&parse_hex(
+            ty,
+            expect_scalar(ty, value)?,
+        )?)),
+        AbiType::String => Ok(encode_dynamic_bytes(expect_scalar(ty, value)?.as_bytes())),
+        AbiType::Array(inner) => {
+            let items = expect_list(ty, value, None)?;
+            let mut encoded = uint_word(items.len());
+            let types = vec![(**inner).clone(); items.len()];
+            encoded.extend(encode_sequence(&types, items)?);
+            Ok(encoded)
+        }
+        AbiType::FixedArray(inner, len) => {
+            let items = expect_list(ty, value, Some(*len))?;
+            let types = vec![(**inner).clone(); *len];
+            encode_sequence(&types, items)
+        }
+        AbiType::Tuple(types) => {
+            let items = expect_list(ty, value, Some(types.len()))?;
+            encode_sequence(types, items)
+        }
+    }
+}
+
+fn encode_dynamic_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = uint_word(bytes.len());
+    encoded.extend(bytes);
+    encoded.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+    encoded
+}
+
+fn uint_word(value: usize) -> Vec<u8> {
+    let mut word = vec![0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+/// Returns `true` if the big-endian word is smaller than `2^bits`.
+fn fits_in_bits(word: &[u8; 32], bits: usize) -> bool {
+    let free_bits = 256 - bits;
+    word.iter().enumerate().all(|(i, byte)| {
+        let bit_start = i * 8;
+        if bit_start + 8 <= free_bits {
+            *byte == 0
+        } else if bit_start < free_bits {
+            byte >> (8 - (free_bits - bit_start)) == 0
+        } else {
+            true
+        }
+    })
+}
+
+/// Returns `2^(bits - 1)`, the magnitude of the smallest `intN`.
+fn min_magnitude(bits: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    let bit = bits - 1;
+    word[31 - bit / 8] = 1 << (bit % 8);
+    word
+}
+
+/// Returns the two's complement of the word.
+fn negate(word: [u8; 32]) -> [u8; 32] {
+    let mut result = word.map(|b| !b);
+    for byte in result.iter_mut().rev() {
+        let (sum, overflow) = byte.overflowing_add(1);
+        *byte = sum;
+        if !overflow {
+            break;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_hex(types: &[&str], values: &[&str]) -> String {
+        hex::encode(encode(types, values).expect("Encoding error."))
+    }
+
+    fn encode_packed_hex(types: &[&str], values: &[&str]) -> String {
+        hex::encode(encode_packed(types, values).expect("Encoding error."))
+    }
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!("uint".parse::<AbiType>().unwrap(), AbiType::Uint(256));
+        assert_eq!(
+            "(address,uint256)[]".parse::<AbiType>().unwrap(),
+            AbiType::Array(Box::new(AbiType::Tuple(vec![
+                AbiType::Address,
+                AbiType::Uint(256)
+            ])))
+        );
+        assert_eq!(
+            "uint8[2][]".parse::<AbiType>().unwrap(),
+            AbiType::Array(Box::new(AbiType::FixedArray(Box::new(AbiType::Uint(8)), 2)))
+        );
+        assert!("uint7".parse::<AbiType>().is_err());
+        assert!("bytes33".parse::<AbiType>().is_err());
+        assert!("foo".parse::<AbiType>().is_err());
+    }
+
+    /// in solidity
+    /// abi.encode(0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, 100)
+    #[test]
+    fn test_encode_address_uint256() {
+        assert_eq!(
+            encode_hex(&["address", "uint256"], &["0x5B38Da6a701c568545dCfcB03FcB875f56beddC4", "100"]),
+            "0000000000000000000000005b38da6a701c568545dcfcb03fcb875f56beddc40000000000000000000000000000000000000000000000000000000000000064"
+        );
+    }
+
+    /// in solidity
+    /// abi.encode(true, bytes2(0x1234), int8(-1))
+    #[test]
+    fn test_encode_static_types() {
+        assert_eq!(
+            encode_hex(&["bool", "bytes2", "int8"], &["true", "0x1234", "-1"]),
+            [
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "1234000000000000000000000000000000000000000000000000000000000000",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            ]
+            .concat()
+        );
+    }
+
+    /// in solidity
+    /// abi.encode(uint256(1), "hello")
+    #[test]
+    fn test_encode_string() {
+        assert_eq!(
+            encode_hex(&["uint256", "string"], &["1", "hello"]),
+            [
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "0000000000000000000000000000000000000000000000000000000000000005",
+                "68656c6c6f000000000000000000000000000000000000000000000000000000",
+            ]
+            .concat()
+        );
+    }
+
+    /// in solidity
+    /// abi.encode(" alice "), abi.encode("\"x\""), abi.encode("[note]")
+    #[test]
+    fn test_encode_string_verbatim() {
+        for value in [" alice ", "\"x\"", "[note]", "(a, b)"] {
+            let encoded = encode(&["string"], &[value]).expect("Encoding error.");
+            assert_eq!(encoded[63] as usize, value.len());
+            assert_eq!(&encoded[64..64 + value.len()], value.as_bytes());
+            assert_eq!(
+                encode_packed(&["string"], &[value]).expect("Encoding error."),
+                value.as_bytes()
+            );
+        }
+        // Unlike strings, `bytes` values are hex and trimmed like the other hex types.
+        assert_eq!(
+            encode_hex(&["bytes"], &[" 0x1234 "]),
+            encode_hex(&["bytes"], &["0x1234"])
+        );
+        // Inside arrays, strings are trimmed and unquoted.
+        assert_eq!(
+            encode_hex(&["string[]"], &["[\" a, b \", c ]"]),
+            encode_hex(&["string[]"], &["[\" a, b \",c]"])
+        );
+    }
+
+    /// in solidity
+    /// abi.encode([uint256(1), 2], [uint8(3), 4])  // uint256[] and uint8[2]
+    #[test]
+    fn test_encode_arrays() {
+        assert_eq!(
+            encode_hex(&["uint256[]", "uint8[2]"], &["[1, 2]", "[3, 4]"]),
+            [
+                "0000000000000000000000000000000000000000000000000000000000000060",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+            ]
+            .concat()
+        );
+    }
+
+    /// in solidity
+    /// abi.encode(S({a: 1, b: "a"}))  // struct S { uint256 a; string b; }
+    #[test]
+    fn test_encode_dynamic_tuple() {
+        assert_eq!(
+            encode_hex(&["(uint256,string)"], &["(1, \"a\")"]),
+            [
+                "0000000000000000000000000000000000000000000000000000000000000020",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "6100000000000000000000000000000000000000000000000000000000000000",
+            ]
+            .concat()
+        );
+    }
+
+    /// in solidity
+    /// abi.encodePacked(0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, uint16(1), "ab", true)
+    #[test]
+    fn test_encode_packed() {
+        assert_eq!(
+            encode_packed_hex(
+                &["address", "uint16", "string", "bool"],
+                &[
+                    "0x5B38Da6a701c568545dCfcB03FcB875f56beddC4",
+                    "1",
+                    "ab",
+                    "true"
+                ]
+            ),
+            "5b38da6a701c568545dcfcb03fcb875f56beddc40001616201"
+        );
+        assert_eq!(
+            encode_packed_hex(&["uint8[]"], &["[1, 2]"]),
+            [
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+            ]
+            .concat()
+        );
+        assert!(encode_packed(&["(uint256,string)"], &["(1, a)"]).is_err());
+    }
+
+    #[test]
+    fn test_split_values() {
+        assert_eq!(
+            split_values("0xabc, [1, 2], (\"a, b\", true)").expect("Failed to split values"),
+            vec!["0xabc", "[1, 2]", "(\"a, b\", true)"]
+        );
+        assert_eq!(
+            split_values("\"a, b\", \"\\\"x\\\"\"").expect("Failed to split values"),
+            vec!["a, b", "\"x\""]
+        );
+        assert!(split_values("[1, 2").is_err());
+    }
+
+    #[test]
+    fn test_value_from_json() {
+        let value = serde_json::json!(["a, b", 1, [true, "c"]]);
+        let literal = value_from_json(&value).expect("Failed to convert value");
+        assert_eq!(literal, "[\"a, b\", 1, [true, c]]");
+        assert_eq!(
+            encode(&["(string,uint8,(bool,string))"], &[&literal]).ok(),
+            encode(
+                &["(string,uint8,(bool,string))"],
+                &["(\"a, b\", 1, (true, c))"]
+            )
+            .ok()
+        );
+        assert!(value_from_json(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_parse_uint256() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(parse_uint256(max), Some([0xff; 32]));
+        assert_eq!(
+            parse_uint256(&format!("0x{}", "f".repeat(64))),
+            Some([0xff; 32])
+        );
+        assert_eq!(
+            parse_uint256(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            ),
+            None
+        );
+        assert_eq!(parse_uint256("0x1").map(|w| w[31]), Some(1));
+        assert_eq!(parse_uint256("12a"), None);
+    }
+
+    #[test]
+    fn test_integer_bounds() {
+        assert!(encode(&["uint8"], &["255"]).is_ok());
+        assert!(encode(&["uint8"], &["256"]).is_err());
+        assert!(encode(&["int8"], &["127"]).is_ok());
+        assert!(encode(&["int8"], &["128"]).is_err());
+        assert!(encode(&["int8"], &["-128"]).is_ok());
+        assert!(encode(&["int8"], &["-129"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(encode(&["address"], &["0x1234"]).is_err());
+        assert!(encode(&["bool"], &["yes"]).is_err());
+        assert!(encode(&["uint8[2]"], &["[1, 2, 3]"]).is_err());
+        assert!(encode(&["address", "uint256"], &["0x1234"]).is_err());
+    }
+}
@@ -3,7 +3,9 @@
 //! Provides utility functions for handling and manipulating hexadecimal strings representing byte data.
 //!
 
-use crate::utils::errors::BytesError;
+use crate::utils::errors::{BytesError, MerkleError};
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
 use crate::utils::keccak::keccak256;
 
 /// Hashes a pair of hexadecimal strings after sorting them in lexicographic order.
@@ -16,9 +18,9 @@ use crate::utils::keccak::keccak256;
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing the hexadecimal hash string, or a `BytesError` on failure.
+/// Returns a `Result` containing the hexadecimal hash string, or a `MerkleError::Bytes` on failure.
 ///
-pub fn hash_pair(a: &str, b: &str) -> Result<String, BytesError> {
+pub fn hash_pair(a: &str, b: &str) -> Result<String, MerkleError> {
     let sorted = match compare_bytes(a, b) {
         Ok(t) => {
             if t == std::cmp::Ordering::Greater {
@@ -28,18 +30,36 @@ pub fn hash_pair(a: &str, b: &str) -> Result<String, BytesError> {
             }
         }
         Err(_) => {
-            return Err(BytesError::ComparisonFailed(a.to_string(), b.to_string()));
+            return Err(BytesError::ComparisonFailed(a.to_string(), b.to_string()).into());
         }
     };
     let concatenated = match concat_hex_strings(sorted.0, sorted.1) {
         Ok(t) => {
             format!("0x{}", t)
         }
-        Err(_) => return Err(BytesError::ConcatenateError(a.to_string(), b.to_string())),
+        Err(_) => return Err(BytesError::ConcatenateError(a.to_string(), b.to_string()).into()),
     };
     keccak256(concatenated.as_str())
 }
 
+/// Hashes a pair of tree nodes after sorting them in lexicographic order.
+///
+/// This is the binary counterpart of [`hash_pair`], and matches `_hashPair` in OpenZeppelin's `MerkleProof`.
+/// Trees built with another hash function use [`MerkleHasher::hash_pair`] instead.
+///
+/// # Arguments
+///
+/// * `a` - The first node.
+/// * `b` - The second node.
+///
+/// # Returns
+///
+/// Returns the Keccak256 hash of the concatenated nodes.
+///
+pub fn hash_nodes(a: &Hash32, b: &Hash32) -> Hash32 {
+    Keccak256::hash_pair(a, b)
+}
+
 /// Concatenates two hexadecimal strings.
 ///
 ///
@@ -54,11 +74,11 @@ pub fn hash_pair(a: &str, b: &str) -> Result<String, BytesError> {
 ///
 /// # Errors
 ///
-/// Returns a `hex::FromHexError` if there's an error during the decoding of the hexadecimal strings.
+/// Returns a `MerkleError::InvalidHex` if there's an error during the decoding of the hexadecimal strings.
 ///
-pub fn concat_hex_strings(a: &str, b: &str) -> Result<String, hex::FromHexError> {
-    let a_bytes = hex::decode(a)?;
-    let b_bytes = hex::decode(b)?;
+pub fn concat_hex_strings(a: &str, b: &str) -> Result<String, MerkleError> {
+    let a_bytes = decode_hex(a)?;
+    let b_bytes = decode_hex(b)?;
 
     let mut concatenated = a_bytes;
     concatenated.extend(b_bytes);
@@ -80,15 +100,19 @@ pub fn concat_hex_strings(a: &str, b: &str) -> Result<String, hex::FromHexError>
 ///
 /// # Errors
 ///
-/// Returns a `hex::FromHexError` if there's an error during the decoding of the hexadecimal strings.
+/// Returns a `MerkleError::InvalidHex` if there's an error during the decoding of the hexadecimal strings.
 ///
-pub fn compare_bytes(a: &str, b: &str) -> Result<std::cmp::Ordering, hex::FromHexError> {
-    let a_bytes = hex::decode(a)?;
-    let b_bytes = hex::decode(b)?;
+pub fn compare_bytes(a: &str, b: &str) -> Result<std::cmp::Ordering, MerkleError> {
+    let a_bytes = decode_hex(a)?;
+    let b_bytes = decode_hex(b)?;
 
     Ok(a_bytes.cmp(&b_bytes))
 }
 
+fn decode_hex(s: &str) -> Result<Vec<u8>, MerkleError> {
+    hex::decode(s).map_err(|_| MerkleError::InvalidHex(s.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +129,15 @@ mod tests {
             "4f1bf293e60209d407901e10ebbdb0da6faaf7ae860c3816321563708f6619bf"
         )
     }
+
+    #[test]
+    fn test_hash_nodes_matches_hash_pair() {
+        let a = "5931b4ed56ace4c46b68524cb5bcbf4195f1bbaacbe5228fbd090546c88dd229";
+        let b = "a6fcf6ee01abd319c4dddfb228856dbc7af3d163647fe57d77640604a69167a4";
+        let expected = hash_pair(a, b).expect("Hash Pair Error");
+        let a: Hash32 = a.parse().expect("Invalid hash");
+        let b: Hash32 = b.parse().expect("Invalid hash");
+        assert_eq!(hash_nodes(&a, &b).to_hex(), expected);
+        assert_eq!(hash_nodes(&b, &a).to_hex(), expected);
+    }
 }
@@ -1,6 +1,8 @@
 //! # Errors
 //!
-//! Represents potential errors that can arise while handling byte operations.
+//! Represents potential errors that can arise while handling byte operations, encoding leaves and building trees.
+//!
+//! Every public function of the crate returns [`MerkleError`], which wraps the lower level [`BytesError`] and [`AbiError`].
 
 use std::fmt::Display;
 
@@ -40,3 +42,138 @@ impl Display for BytesError {
 }
 
 impl std::error::Error for BytesError {}
+
+/// Represents potential errors that can arise while ABI encoding leaf values.
+///
+/// # Variants
+///
+/// * `InvalidType`: Indicates a Solidity type string couldn't be parsed.
+/// * `InvalidValue`: Indicates a value couldn't be parsed as its type.
+/// * `LengthMismatch`: Indicates the number of types and values differ.
+/// * `UnsupportedPacked`: Indicates a type that can't be used with `abi.encodePacked`.
+///
+#[derive(Debug)]
+pub enum AbiError {
+    InvalidType(String),
+    InvalidValue(String, String),
+    LengthMismatch(usize, usize),
+    UnsupportedPacked(String),
+}
+
+impl Display for AbiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AbiError::InvalidType(t) => write!(f, "Invalid Solidity type {}", t),
+            AbiError::InvalidValue(t, v) => write!(f, "Invalid value {} for type {}", v, t),
+            AbiError::LengthMismatch(t, v) => {
+                write!(
+                    f,
+                    "Expected {} values for the leaf encoding, found {}",
+                    t, v
+                )
+            }
+            AbiError::UnsupportedPacked(t) => {
+                write!(f, "Type {} is not supported by abi.encodePacked", t)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AbiError {}
+
+/// The error type returned by the public functions of the crate.
+///
+/// # Variants
+///
+/// * `Bytes`: Wraps a [`BytesError`] raised while handling hexadecimal byte strings.
+/// * `Abi`: Wraps an [`AbiError`] raised while ABI encoding values.
+/// * `InvalidHex`: Indicates a string isn't valid hexadecimal, or isn't a 32-byte hash where one is expected.
+/// * `LeafEncoding`: Indicates the leaf at the given index couldn't be encoded or hashed.
+/// * `EmptyInput`: Indicates a tree was requested from no leaves.
+/// * `LeafNotFound`: Indicates a leaf isn't part of the tree.
+/// * `InvalidIndex`: Indicates an index that doesn't point to a leaf of the tree, or is given twice.
+/// * `UnsupportedLayout`: Indicates an operation that the layout of the tree doesn't support.
+/// * `InvalidDump`: Indicates a tree dump that can't be parsed or doesn't match its values.
+/// * `InvalidInput`: Indicates a leaf file, such as a CSV or JSON file, that can't be parsed.
+/// * `TreeFull`: Indicates a leaf was appended to a fixed-depth tree that has no room left.
+/// * `Io`: Wraps an I/O error raised while reading or writing files.
+///
+/// # Examples
+///
+/// ```
+/// use eth_merkle_tree::tree::MerkleTree;
+/// use eth_merkle_tree::utils::errors::MerkleError;
+///
+/// let data = vec![String::from("0xabc"), String::from("not hex")];
+/// match MerkleTree::new(&data) {
+///     Err(MerkleError::LeafEncoding(index, _)) => assert_eq!(index, 1),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum MerkleError {
+    Bytes(BytesError),
+    Abi(AbiError),
+    InvalidHex(String),
+    LeafEncoding(usize, String),
+    EmptyInput,
+    LeafNotFound(String),
+    InvalidIndex(usize),
+    UnsupportedLayout(String),
+    InvalidDump(String),
+    InvalidInput(String),
+    TreeFull(usize),
+    Io(std::io::Error),
+}
+
+impl Display for MerkleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MerkleError::Bytes(e) => write!(f, "{}", e),
+            MerkleError::Abi(e) => write!(f, "{}", e),
+            MerkleError::InvalidHex(s) => write!(f, "Invalid hex string {}", s),
+            MerkleError::LeafEncoding(index, reason) => {
+                write!(f, "Invalid leaf at index {}: {}", index, reason)
+            }
+            MerkleError::EmptyInput => write!(f, "Expected non-zero number of leaves"),
+            MerkleError::LeafNotFound(leaf) => write!(f, "Leaf {} not found in the tree", leaf),
+            MerkleError::InvalidIndex(index) => write!(f, "Index {} is not a valid leaf", index),
+            MerkleError::UnsupportedLayout(operation) => {
+                write!(f, "{} is not supported by this tree layout", operation)
+            }
+            MerkleError::InvalidDump(reason) => write!(f, "Invalid tree dump: {}", reason),
+            MerkleError::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            MerkleError::TreeFull(depth) => write!(f, "Merkle tree of depth {} is full", depth),
+            MerkleError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MerkleError::Bytes(e) => Some(e),
+            MerkleError::Abi(e) => Some(e),
+            MerkleError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BytesError> for MerkleError {
+    fn from(e: BytesError) -> Self {
+        MerkleError::Bytes(e)
+    }
+}
+
+impl From<AbiError> for MerkleError {
+    fn from(e: AbiError) -> Self {
+        MerkleError::Abi(e)
+    }
+}
+
+impl From<std::io::Error> for MerkleError {
+    fn from(e: std::io::Error) -> Self {
+        MerkleError::Io(e)
+    }
+}
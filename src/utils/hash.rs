@@ -0,0 +1,133 @@
+//! # Hash
+//!
+//! Provides the 32-byte hash type used for the nodes, proofs and roots of a Merkle Tree.
+//!
+//! Hashes are kept as raw bytes and are only converted to hexadecimal strings through `Display` and `FromStr`.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::utils::hash::Hash32;
+//! let hash: Hash32 = "0x5931b4ed56ace4c46b68524cb5bcbf4195f1bbaacbe5228fbd090546c88dd229"
+//!     .parse()
+//!     .expect("Invalid hash");
+//! assert_eq!(
+//!     hash.to_string(),
+//!     "0x5931b4ed56ace4c46b68524cb5bcbf4195f1bbaacbe5228fbd090546c88dd229"
+//! );
+//! ```
+
+use crate::utils::errors::MerkleError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A 32-byte hash, such as a Keccak256 digest.
+///
+/// Ordering compares the bytes lexicographically, which is the order used to sort pairs and leaves.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hash32(pub [u8; 32]);
+
+impl Hash32 {
+    /// Returns the hash as a byte array.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns the hash as a lowercase hexadecimal string without the `0x` prefix.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Returns the hash shortened to its first and last `digits` hexadecimal digits, like `0x1234…abcd`.
+    ///
+    /// The full `0x`-prefixed hash is returned when it isn't longer than the shortened one.
+    pub fn abbreviate(&self, digits: usize) -> String {
+        let hex = self.to_hex();
+        if 2 * digits >= hex.len() {
+            return format!("0x{}", hex);
+        }
+        format!("0x{}…{}", &hex[..digits], &hex[hex.len() - digits..])
+    }
+}
+
+impl From<[u8; 32]> for Hash32 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Hash32(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Hash32 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Hash32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", self.to_hex())
+    }
+}
+
+impl fmt::Debug for Hash32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash32({})", self)
+    }
+}
+
+impl FromStr for Hash32 {
+    type Err = MerkleError;
+
+    /// Parses a 64-character hexadecimal string, with or without the `0x` prefix.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(digits, &mut bytes)
+            .map_err(|_| MerkleError::InvalidHex(s.to_string()))?;
+        Ok(Hash32(bytes))
+    }
+}
+
+impl Serialize for Hash32 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash32 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_and_without_prefix() {
+        let digits = "4f1bf293e60209d407901e10ebbdb0da6faaf7ae860c3816321563708f6619bf";
+        let with_prefix: Hash32 = format!("0x{}", digits).parse().expect("Invalid hash");
+        let without_prefix: Hash32 = digits.parse().expect("Invalid hash");
+        assert_eq!(with_prefix, without_prefix);
+        assert_eq!(without_prefix.to_hex(), digits);
+        assert_eq!(without_prefix.to_string(), format!("0x{}", digits));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("0x1234".parse::<Hash32>().is_err());
+        assert!("zz".repeat(32).parse::<Hash32>().is_err());
+    }
+
+    #[test]
+    fn test_abbreviate() {
+        let hash: Hash32 = "4f1bf293e60209d407901e10ebbdb0da6faaf7ae860c3816321563708f6619bf"
+            .parse()
+            .expect("Invalid hash");
+        assert_eq!(hash.abbreviate(4), "0x4f1b…19bf");
+        assert_eq!(hash.abbreviate(0), "0x…");
+        assert_eq!(hash.abbreviate(32), hash.to_string());
+        assert_eq!(hash.abbreviate(100), hash.to_string());
+    }
+}
@@ -0,0 +1,155 @@
+//! # Hasher
+//!
+//! Provides the hash functions a Merkle Tree can be built with.
+//!
+//! [`Keccak256`] is the default and matches Solidity and OpenZeppelin. [`Sha256`] matches Bitcoin-style
+//! commitments, and [`Blake2b`] and [`Blake3`] are available for systems built on them.
+//!
+//! # Example
+//! ```
+//! use eth_merkle_tree::tree::MerkleTree;
+//! use eth_merkle_tree::utils::hasher::Sha256;
+//!
+//! let data = vec![String::from("0xabc"), String::from("0xdef")];
+//! let tree = MerkleTree::<Sha256>::new_with_hasher(&data).expect("Tree creation error.");
+//! ```
+
+use crate::utils::abi;
+use crate::utils::errors::MerkleError;
+use crate::utils::hash::Hash32;
+use crate::utils::keccak::leaf_bytes;
+use sha2::Digest;
+
+/// A 32-byte hash function used for the leaves and nodes of a Merkle Tree.
+///
+/// Only [`MerkleHasher::hash`] has to be implemented. The other methods build the leaf and pair hashes
+/// on top of it the same way for every hash function.
+pub trait MerkleHasher {
+    /// The Solidity builtin computing the same hash, if any, used by generated verifier contracts.
+    const SOLIDITY_FUNCTION: Option<&'static str> = None;
+
+    /// Hashes raw bytes.
+    fn hash(data: &[u8]) -> Hash32;
+
+    /// Hashes a pair of nodes after sorting them in lexicographic order, like `_hashPair` in OpenZeppelin's `MerkleProof`.
+    fn hash_pair(a: &Hash32, b: &Hash32) -> Hash32 {
+        if a <= b {
+            Self::hash_concat(a, b)
+        } else {
+            Self::hash_concat(b, a)
+        }
+    }
+
+    /// Hashes a pair of nodes in the given order, `hash(left ++ right)`.
+    fn hash_concat(left: &Hash32, right: &Hash32) -> Hash32 {
+        let mut concatenated = [0u8; 64];
+        concatenated[..32].copy_from_slice(left.as_bytes());
+        concatenated[32..].copy_from_slice(right.as_bytes());
+        Self::hash(&concatenated)
+    }
+
+    /// Hashes a raw leaf, decoded as described in [`crate::utils::keccak::leaf_hash`].
+    ///
+    /// # Errors
+    /// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+    fn leaf_hash(input: &str) -> Result<Hash32, MerkleError> {
+        Ok(Self::hash(&leaf_bytes(input)?))
+    }
+
+    /// Hashes a raw leaf twice, like the leaves of OpenZeppelin's `StandardMerkleTree`.
+    ///
+    /// # Errors
+    /// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+    fn standard_leaf_hash(input: &str) -> Result<Hash32, MerkleError> {
+        Ok(Self::hash(Self::leaf_hash(input)?.as_bytes()))
+    }
+
+    /// Hashes the ABI encoding of typed values twice, like `StandardMerkleTree.of`.
+    ///
+    /// # Errors
+    /// Returns a [`MerkleError::Abi`] if the values can't be encoded with the given types.
+    fn standard_leaf_hash_typed<T: AsRef<str>, V: AsRef<str>>(
+        types: &[T],
+        values: &[V],
+    ) -> Result<Hash32, MerkleError> {
+        let inner = Self::hash(&abi::encode(types, values)?);
+        Ok(Self::hash(inner.as_bytes()))
+    }
+}
+
+/// Keccak-256, as used by Solidity's `keccak256`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keccak256;
+
+/// SHA-256, as used by Bitcoin and Solidity's `sha256`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256;
+
+/// BLAKE2b with a 32-byte output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Blake2b;
+
+/// BLAKE3 with its default 32-byte output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Blake3;
+
+impl MerkleHasher for Keccak256 {
+    const SOLIDITY_FUNCTION: Option<&'static str> = Some("keccak256");
+
+    fn hash(data: &[u8]) -> Hash32 {
+        Hash32(sha3::Keccak256::digest(data).into())
+    }
+}
+
+impl MerkleHasher for Sha256 {
+    const SOLIDITY_FUNCTION: Option<&'static str> = Some("sha256");
+
+    fn hash(data: &[u8]) -> Hash32 {
+        Hash32(sha2::Sha256::digest(data).into())
+    }
+}
+
+impl MerkleHasher for Blake2b {
+    fn hash(data: &[u8]) -> Hash32 {
+        Hash32(blake2::Blake2b::<blake2::digest::consts::U32>::digest(data).into())
+    }
+}
+
+impl MerkleHasher for Blake3 {
+    fn hash(data: &[u8]) -> Hash32 {
+        Hash32(blake3::hash(data).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_input_digests() {
+        assert_eq!(
+            Keccak256::hash(b"").to_hex(),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            Sha256::hash(b"").to_hex(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            Blake2b::hash(b"").to_hex(),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        assert_eq!(
+            Blake3::hash(b"").to_hex(),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn test_hash_pair_is_sorted() {
+        let a = Sha256::hash(b"a");
+        let b = Sha256::hash(b"b");
+        assert_eq!(Sha256::hash_pair(&a, &b), Sha256::hash_pair(&b, &a));
+        assert_ne!(Sha256::hash_pair(&a, &b), Keccak256::hash_pair(&a, &b));
+    }
+}
@@ -10,65 +10,94 @@
 //!  println!("Keccak256 hash: {}", hash);
 //!  ```
 
-use crate::utils::errors::BytesError;
-use sha3::{Digest, Keccak256};
+use crate::utils::abi;
+use crate::utils::errors::MerkleError;
+use crate::utils::hash::Hash32;
+use crate::utils::hasher::{Keccak256, MerkleHasher};
 
 /// Computes the Keccak256 hash of the given input.
 ///
 ///
 /// # Errors
-/// Returns a [`BytesError::KeccakError`] if there's an issue during the hashing process.
-///
-pub fn keccak256(input: &str) -> Result<String, BytesError> {
-    let mut value;
-    if input.contains(",") {
-        let inputs: Vec<&str> = input.split(",").collect();
-        value = encode_packed(inputs[0].trim(), inputs[1].trim());
-    } else {
-        value = if let Some(stripped) = input.strip_prefix("0x") {
-            stripped.to_string()
-        } else {
-            input.to_string()
-        };
+/// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+///
+pub fn keccak256(input: &str) -> Result<String, MerkleError> {
+    leaf_hash(input).map(|hash| hash.to_hex())
+}
 
-        if value.len() % 2 != 0 {
-            value.insert(0, '0');
-        }
+/// Computes the Keccak256 hash of the given input as a [`Hash32`].
+///
+/// The input is decoded the same way as in [`keccak256`]: an `address, amount` pair is ABI encoded,
+/// anything else is read as hexadecimal bytes.
+///
+/// # Errors
+/// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+///
+pub fn leaf_hash(input: &str) -> Result<Hash32, MerkleError> {
+    Keccak256::leaf_hash(input)
+}
+
+/// Decodes a raw leaf into the bytes that are hashed.
+///
+/// An `address, amount` pair is ABI encoded, anything else is read as hexadecimal bytes,
+/// with or without the `0x` prefix. An odd number of digits is padded with a leading zero.
+///
+/// # Errors
+/// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+///
+pub fn leaf_bytes(input: &str) -> Result<Vec<u8>, MerkleError> {
+    if let Some((address, amount)) = input.split_once(',') {
+        return abi::encode(&["address", "uint256"], &[address.trim(), amount.trim()]);
+    }
+    let mut value = input.strip_prefix("0x").unwrap_or(input).to_string();
+    if !value.len().is_multiple_of(2) {
+        value.insert(0, '0');
     }
-    let hash = match hex::decode(&value) {
-        Ok(t) => t,
-        Err(e) => {
-            println!("{}", e);
-            return Err(BytesError::KeccakError(value));
-        }
-    };
-    let mut hasher = Keccak256::new();
-    hasher.update(&*hash);
-    let result = hasher.finalize();
-    let hex_string: Vec<String> = result.iter().map(|b| format!("{:02x}", b)).collect();
-    Ok(hex_string.concat())
+    hex::decode(&value).map_err(|_| MerkleError::InvalidHex(input.to_string()))
+}
+
+/// Computes the Keccak256 hash of raw bytes, such as the output of `utils::abi::encode`.
+///
+pub fn keccak256_bytes(data: &[u8]) -> Hash32 {
+    Keccak256::hash(data)
+}
+
+/// Computes the leaf hash used by OpenZeppelin's `StandardMerkleTree`.
+///
+/// The leaf is encoded the same way [`keccak256`] encodes it and then hashed twice,
+/// mirroring `keccak256(bytes.concat(keccak256(abi.encode(...))))` in Solidity.
+/// An `address, amount` input therefore matches a `["address", "uint256"]` leaf encoding.
+///
+/// # Errors
+/// Returns a [`MerkleError::InvalidHex`] or [`MerkleError::Abi`] if the input can't be decoded.
+///
+pub fn standard_leaf_hash(input: &str) -> Result<Hash32, MerkleError> {
+    Keccak256::standard_leaf_hash(input)
+}
+
+/// Computes the `StandardMerkleTree` leaf hash of typed values, `keccak256(bytes.concat(keccak256(abi.encode(values))))`.
+///
+/// # Errors
+/// Returns a [`MerkleError::Abi`] if the values can't be encoded with the given types.
+///
+pub fn standard_leaf_hash_typed<T: AsRef<str>, V: AsRef<str>>(
+    types: &[T],
+    values: &[V],
+) -> Result<Hash32, MerkleError> {
+    Keccak256::standard_leaf_hash_typed(types, values)
 }
 
 /// Encodes two strings in a packed format.
 ///
+/// The amount is a `uint256`, given in decimal or as `0x`-prefixed hex.
 ///
-/// # Panics
+/// # Errors
 ///
-/// When there's an error in decoding the address or parsing the amount.
+/// Returns a [`MerkleError::Abi`] when there's an error in decoding the address or parsing the amount.
 ///
-pub fn encode_packed(addr_str: &str, amount_str: &str) -> String {
-    let addr_bytes = hex::decode(&addr_str[2..]).expect("Failed to decode address");
-    let mut addr_padded = vec![0u8; 32];
-    addr_padded[12..].copy_from_slice(&addr_bytes);
-    let amount: u64 = amount_str.parse().expect("Failed to parse amount");
-    let mut amount_bytes = [0u8; 32];
-    for (i, byte) in amount.to_be_bytes().iter().rev().enumerate() {
-        amount_bytes[31 - i] = *byte;
-    }
-    let mut packed = Vec::new();
-    packed.extend_from_slice(&addr_padded);
-    packed.extend_from_slice(&amount_bytes);
-    packed.iter().map(|byte| format!("{:02x}", byte)).collect()
+pub fn encode_packed(addr_str: &str, amount_str: &str) -> Result<String, MerkleError> {
+    let packed = abi::encode(&["address", "uint256"], &[addr_str, amount_str])?;
+    Ok(hex::encode(packed))
 }
 
 #[cfg(test)]
@@ -95,20 +124,52 @@ mod tests {
             "a6fcf6ee01abd319c4dddfb228856dbc7af3d163647fe57d77640604a69167a4"
         );
     }
-    #[should_panic(expected = "Failed to parse amount: ParseIntError { kind: InvalidDigit }")]
     #[test]
     fn test_keccak256_address_amount_failed() {
-        let hash =
-            keccak256("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, a").expect("Keccak Error.");
-        assert_eq!(hash, "no hash");
+        let result = keccak256("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, a");
+        assert!(matches!(result, Err(MerkleError::Abi(_))));
+    }
+    #[test]
+    fn test_keccak256_invalid_hex() {
+        let result = keccak256("0xnothex");
+        assert!(matches!(result, Err(MerkleError::InvalidHex(_))));
     }
     /// in solidity
     /// keccak256(abi.encode(0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, 100))
     #[test]
     fn test_encode_packed() {
         assert_eq!(
-            encode_packed("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4","100"),
+            encode_packed("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4","100").expect("Encoding Error."),
             "0000000000000000000000005b38da6a701c568545dcfcb03fcb875f56beddc40000000000000000000000000000000000000000000000000000000000000064"
         );
     }
+    /// in solidity
+    /// abi.encode(0x5B38Da6a701c568545dCfcB03FcB875f56beddC4, 1000000 ether)
+    #[test]
+    fn test_encode_packed_large_amount() {
+        assert_eq!(
+            encode_packed("0x5B38Da6a701c568545dCfcB03FcB875f56beddC4", "1000000000000000000000000").expect("Encoding Error."),
+            "0000000000000000000000005b38da6a701c568545dcfcb03fcb875f56beddc400000000000000000000000000000000000000000000d3c21bcecceda1000000"
+        );
+        assert_eq!(
+            encode_packed(
+                "0x5B38Da6a701c568545dCfcB03FcB875f56beddC4",
+                "0xd3c21bcecceda1000000"
+            )
+            .ok(),
+            encode_packed(
+                "0x5B38Da6a701c568545dCfcB03FcB875f56beddC4",
+                "1000000000000000000000000"
+            )
+            .ok()
+        );
+    }
+    #[test]
+    fn test_encode_packed_amount_overflow() {
+        assert!(encode_packed(
+            "0x5B38Da6a701c568545dCfcB03FcB875f56beddC4",
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+        )
+        .is_err());
+    }
 }
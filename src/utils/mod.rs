@@ -1,6 +1,9 @@
 //! # Utils
 //!
+pub mod abi;
 pub mod bytes;
 pub mod errors;
+pub mod hash;
+pub mod hasher;
 pub mod keccak;
 pub mod verify;
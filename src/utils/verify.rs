@@ -1,27 +1,369 @@
-use crate::utils::{bytes::hash_pair, errors::BytesError, keccak::keccak256};
-pub fn verify_proof(proof: Vec<String>, root: &str, leaf_data: &str) -> Result<bool, BytesError> {
-    let leaf_hash = keccak256(leaf_data)?;
+use crate::tree::{MultiProof, Side, SparseProof};
+use crate::utils::{
+    errors::MerkleError,
+    hash::Hash32,
+    hasher::{Keccak256, MerkleHasher},
+};
+use std::collections::VecDeque;
+
+/// The outcome of walking a proof from its leaf up to the root, see [`check_proof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofCheck {
+    root: Hash32,
+    /// Never empty, it starts with the leaf hash.
+    path: Vec<Hash32>,
+}
+
+impl ProofCheck {
+    /// Returns the root the proof was checked against.
+    pub fn root(&self) -> Hash32 {
+        self.root
+    }
+
+    /// Returns the node computed at each step: the leaf hash, then the node after hashing each sibling.
+    /// The last node is the computed root.
+    pub fn path(&self) -> &[Hash32] {
+        &self.path
+    }
+
+    /// Returns the root computed from the leaf and the proof.
+    pub fn computed_root(&self) -> Hash32 {
+        *self
+            .path
+            .last()
+            .expect("The path starts with the leaf hash")
+    }
+
+    /// Returns `true` if the computed root is the expected one.
+    pub fn is_valid(&self) -> bool {
+        self.computed_root() == self.root
+    }
+
+    /// Returns the first step at which the computed path leaves `expected_path`, such as the path
+    /// returned by `MerkleTree::proof_path` for the leaf.
+    ///
+    /// Step `0` means the leaf hash itself differs, and step `i` that the sibling `proof[i - 1]` is wrong.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(step)` if the paths differ at `step`, or if one of them ends at `step`.
+    /// - `None` if both paths are the same.
+    pub fn divergence(&self, expected_path: &[Hash32]) -> Option<usize> {
+        self.path
+            .iter()
+            .zip(expected_path)
+            .position(|(computed, expected)| computed != expected)
+            .or_else(|| {
+                (self.path.len() != expected_path.len())
+                    .then(|| self.path.len().min(expected_path.len()))
+            })
+    }
+}
+
+/// Walks a proof generated by a tree built with `MerkleTree::new` or `MerkleTree::new_standard`.
+///
+/// Unlike [`verify_proof`], the proof is borrowed, its hashes may omit the `0x` prefix, and the
+/// computed path is returned so a failing proof can be compared with the expected one.
+///
+/// # Arguments
+///
+/// * `proof` - The sibling hashes, from the leaf up to the root.
+/// * `root` - The expected root.
+/// * `leaf_hash` - The hash of the leaf, hashed the way the tree hashed its leaves.
+///
+/// # Errors
+///
+/// - When the root or a sibling isn't a 32-byte hex hash.
+///
+/// # Example
+///
+/// ```
+/// use eth_merkle_tree::tree::MerkleTree;
+/// use eth_merkle_tree::utils::keccak::leaf_hash;
+/// use eth_merkle_tree::utils::verify::check_proof;
+///
+/// let data = vec![String::from("0xabc"), String::from("0xdef"), String::from("0x123")];
+/// let tree = MerkleTree::new(&data).expect("Tree creation error.");
+/// let root = tree.root.as_ref().unwrap().data.to_hex();
+/// let proof: Vec<String> = tree.generate_proof(0).unwrap().iter().map(|h| h.to_hex()).collect();
+///
+/// let check = check_proof(&proof[..1], &root, &leaf_hash("0xabc").unwrap()).expect("Invalid hex.");
+/// assert!(!check.is_valid());
+/// assert_eq!(check.divergence(&tree.proof_path(0).unwrap()), Some(2));
+/// ```
+pub fn check_proof<S: AsRef<str>>(
+    proof: &[S],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<ProofCheck, MerkleError> {
+    check_proof_with_hasher::<Keccak256, S>(proof, root, leaf_hash)
+}
+
+/// Walks a proof generated by a tree built with another [`MerkleHasher`], like [`check_proof`].
+pub fn check_proof_with_hasher<H: MerkleHasher, S: AsRef<str>>(
+    proof: &[S],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<ProofCheck, MerkleError> {
+    Ok(ProofCheck {
+        root: root.trim().parse()?,
+        path: walk_proof::<H, S>(proof, None, leaf_hash)?,
+    })
+}
+
+/// Walks a proof generated by a tree built with `PairHashing::Positional`, hashing each sibling on its side.
+///
+/// # Errors
+///
+/// - When the root or a sibling isn't a 32-byte hex hash.
+/// - When `sides` and `proof` differ in length.
+pub fn check_positional_proof<S: AsRef<str>>(
+    proof: &[S],
+    sides: &[Side],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<ProofCheck, MerkleError> {
+    check_positional_proof_with_hasher::<Keccak256, S>(proof, sides, root, leaf_hash)
+}
+
+/// Walks a positional proof generated by a tree built with another [`MerkleHasher`].
+pub fn check_positional_proof_with_hasher<H: MerkleHasher, S: AsRef<str>>(
+    proof: &[S],
+    sides: &[Side],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<ProofCheck, MerkleError> {
+    Ok(ProofCheck {
+        root: root.trim().parse()?,
+        path: walk_proof::<H, S>(proof, Some(sides), leaf_hash)?,
+    })
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::new`.
+///
+/// The leaf is hashed with `leaf_hash`. See [`check_proof`] to borrow the proof and find where an
+/// invalid proof goes wrong.
+pub fn verify_proof(proof: Vec<String>, root: &str, leaf_data: &str) -> Result<bool, MerkleError> {
+    verify_proof_with_hasher::<Keccak256>(proof, root, leaf_data)
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::new_with_hasher`.
+pub fn verify_proof_with_hasher<H: MerkleHasher>(
+    proof: Vec<String>,
+    root: &str,
+    leaf_data: &str,
+) -> Result<bool, MerkleError> {
+    let leaf_hash = H::leaf_hash(leaf_data)?;
+    process_proof::<H>(proof, root, leaf_hash)
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::new_standard`.
+///
+/// The leaf is hashed with `standard_leaf_hash`, the same way OpenZeppelin's `StandardMerkleTree` does.
+pub fn verify_standard_proof(
+    proof: Vec<String>,
+    root: &str,
+    leaf_data: &str,
+) -> Result<bool, MerkleError> {
+    verify_standard_proof_with_hasher::<Keccak256>(proof, root, leaf_data)
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::new_standard_with_hasher`.
+pub fn verify_standard_proof_with_hasher<H: MerkleHasher>(
+    proof: Vec<String>,
+    root: &str,
+    leaf_data: &str,
+) -> Result<bool, MerkleError> {
+    let leaf_hash = H::standard_leaf_hash(leaf_data)?;
+    process_proof::<H>(proof, root, leaf_hash)
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::of`.
+///
+/// The leaf is hashed with `standard_leaf_hash_typed` from its `values` and their Solidity `types`.
+pub fn verify_typed_proof<T: AsRef<str>, V: AsRef<str>>(
+    proof: Vec<String>,
+    root: &str,
+    types: &[T],
+    values: &[V],
+) -> Result<bool, MerkleError> {
+    verify_typed_proof_with_hasher::<Keccak256, _, _>(proof, root, types, values)
+}
+
+/// Verifies a proof generated by a tree built with `MerkleTree::of_with_hasher`.
+pub fn verify_typed_proof_with_hasher<H: MerkleHasher, T: AsRef<str>, V: AsRef<str>>(
+    proof: Vec<String>,
+    root: &str,
+    types: &[T],
+    values: &[V],
+) -> Result<bool, MerkleError> {
+    let leaf_hash = H::standard_leaf_hash_typed(types, values)?;
+    process_proof::<H>(proof, root, leaf_hash)
+}
+
+/// Verifies a proof generated by a tree built with `PairHashing::Positional`, like the deposit contract's
+/// `is_valid_merkle_branch`.
+///
+/// Each sibling is hashed on the side given by `sides`, as returned by `MerkleTree::generate_directed_proof`.
+/// The leaf is given by its hash, since positional trees can use any leaf encoding.
+///
+/// # Returns
+///
+/// `true` if the leaf is part of the tree with the given `root`, `false` otherwise.
+///
+/// # Errors
+///
+/// - When the root or a sibling isn't a 32-byte hex hash.
+/// - When `sides` and `proof` differ in length.
+pub fn verify_positional_proof(
+    proof: Vec<String>,
+    sides: &[Side],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<bool, MerkleError> {
+    verify_positional_proof_with_hasher::<Keccak256>(proof, sides, root, leaf_hash)
+}
+
+/// Verifies a positional proof generated by a tree built with another [`MerkleHasher`].
+pub fn verify_positional_proof_with_hasher<H: MerkleHasher>(
+    proof: Vec<String>,
+    sides: &[Side],
+    root: &str,
+    leaf_hash: &Hash32,
+) -> Result<bool, MerkleError> {
+    let root: Hash32 = root.trim().parse()?;
+    let path = walk_proof::<H, String>(&proof, Some(sides), leaf_hash)?;
+    Ok(path.last() == Some(&root))
+}
+
+/// Verifies a proof of inclusion or non-inclusion generated by a `SparseMerkleTree`.
+///
+/// # Returns
+///
+/// `true` if the key of `proof` holds its value, or is absent when the value is `None`, in the tree with
+/// the given `root`. `false` otherwise, including when the proof is malformed.
+pub fn verify_sparse_proof(proof: &SparseProof, root: &Hash32) -> bool {
+    verify_sparse_proof_with_hasher::<Keccak256>(proof, root)
+}
+
+/// Verifies a sparse proof generated by a tree built with another [`MerkleHasher`].
+pub fn verify_sparse_proof_with_hasher<H: MerkleHasher>(
+    proof: &SparseProof,
+    root: &Hash32,
+) -> bool {
     proof
-        .iter()
-        .map(|h| h[2..].to_string())
-        .try_fold(leaf_hash.clone(), |acc, sibling_hash| {
-            hash_pair(&acc, &sibling_hash)
-        })
-        .map(|computed_root| computed_root == root)
+        .compute_root::<H>()
+        .is_some_and(|computed_root| computed_root == *root)
+}
+
+/// Verifies a multiproof against a root, following `MerkleProof.multiProofVerify`.
+///
+/// # Returns
+///
+/// `true` if the leaves of `multi_proof` are part of the tree with the given `root`, `false` otherwise,
+/// including when the proof is malformed.
+pub fn verify_multi_proof(multi_proof: &MultiProof, root: &Hash32) -> bool {
+    verify_multi_proof_with_hasher::<Keccak256>(multi_proof, root)
+}
+
+/// Verifies a multiproof generated by a tree built with another [`MerkleHasher`].
+pub fn verify_multi_proof_with_hasher<H: MerkleHasher>(
+    multi_proof: &MultiProof,
+    root: &Hash32,
+) -> bool {
+    process_multi_proof::<H>(multi_proof).is_some_and(|computed_root| computed_root == *root)
+}
+
+fn process_multi_proof<H: MerkleHasher>(multi_proof: &MultiProof) -> Option<Hash32> {
+    let MultiProof {
+        leaves,
+        proof,
+        proof_flags,
+    } = multi_proof;
+    if leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return None;
+    }
+    if proof_flags.is_empty() {
+        return leaves.first().or(proof.first()).copied();
+    }
+    // Leaves are consumed before computed hashes, so a single queue reproduces the Solidity ordering.
+    let mut queue: VecDeque<Hash32> = leaves.iter().copied().collect();
+    let mut proof = proof.iter();
+    for flag in proof_flags {
+        let a = queue.pop_front()?;
+        let b = if *flag {
+            queue.pop_front()?
+        } else {
+            *proof.next()?
+        };
+        queue.push_back(H::hash_pair(&a, &b));
+    }
+    if proof.next().is_some() {
+        return None;
+    }
+    queue.back().copied()
+}
+
+fn process_proof<H: MerkleHasher>(
+    proof: Vec<String>,
+    root: &str,
+    leaf_hash: Hash32,
+) -> Result<bool, MerkleError> {
+    let root: Hash32 = root.trim().parse()?;
+    let path = walk_proof::<H, String>(&proof, None, &leaf_hash)?;
+    Ok(path.last() == Some(&root))
+}
+
+/// Returns the leaf hash followed by the node computed after each sibling, hashing pairs sorted
+/// or, given `sides`, positionally.
+fn walk_proof<H: MerkleHasher, S: AsRef<str>>(
+    proof: &[S],
+    sides: Option<&[Side]>,
+    leaf_hash: &Hash32,
+) -> Result<Vec<Hash32>, MerkleError> {
+    if let Some(sides) = sides.filter(|sides| sides.len() != proof.len()) {
+        return Err(MerkleError::InvalidInput(format!(
+            "Expected one side per sibling, got {} sides for {} siblings",
+            sides.len(),
+            proof.len()
+        )));
+    }
+    let mut path = Vec::with_capacity(proof.len() + 1);
+    path.push(*leaf_hash);
+    for (step, sibling) in proof.iter().enumerate() {
+        let sibling: Hash32 = sibling.as_ref().trim().parse()?;
+        let node = path[step];
+        path.push(match sides.map(|sides| sides[step]) {
+            None => H::hash_pair(&node, &sibling),
+            Some(Side::Left) => H::hash_concat(&sibling, &node),
+            Some(Side::Right) => H::hash_concat(&node, &sibling),
+        });
+    }
+    Ok(path)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tree::MerkleTree;
+    use crate::tree::{test_values, MerkleTree, PairHashing};
+    use crate::utils::hasher::{Blake2b, Blake3, Sha256};
+    use crate::utils::keccak::{leaf_hash, standard_leaf_hash};
+
+    fn to_strings(proof: Vec<Hash32>) -> Vec<String> {
+        proof.iter().map(Hash32::to_string).collect()
+    }
+
     #[test]
     fn test_verify_proof_singleton() {
         let data = vec!["0xabc".to_string()];
         let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
-        let root = tree.root.as_ref().expect("No root found").data.clone();
-        let leaf_hash = keccak256(&data[0]).expect("Keccak error.");
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
+        let leaf_hash = leaf_hash(&data[0]).expect("Keccak error.");
         let leaf_index = tree.locate_leaf(&leaf_hash).expect("Failed to locate leaf");
-        let proof = tree.generate_proof(leaf_index);
+        let proof = to_strings(
+            tree.generate_proof(leaf_index)
+                .expect("Failed to generate proof"),
+        );
         let result = verify_proof(proof, &root, &data[0]);
         assert!(result.is_ok());
         assert!(result.unwrap());
@@ -31,11 +373,14 @@ mod tests {
     fn test_verify_proof_two_elements() {
         let data = vec!["0xabc".to_string(), "0xdef".to_string()];
         let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
-        let root = tree.root.as_ref().expect("No root found").data.clone();
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
         for leaf in data.iter() {
-            let leaf_hash = keccak256(leaf).expect("Keccak error.");
+            let leaf_hash = leaf_hash(leaf).expect("Keccak error.");
             let leaf_index = tree.locate_leaf(&leaf_hash).expect("Failed to locate leaf");
-            let proof = tree.generate_proof(leaf_index);
+            let proof = to_strings(
+                tree.generate_proof(leaf_index)
+                    .expect("Failed to generate proof"),
+            );
             let result = verify_proof(proof, &root, leaf);
             assert!(result.is_ok());
             assert!(result.unwrap());
@@ -52,14 +397,237 @@ mod tests {
             "0x789".to_string(),
         ];
         let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
-        let root = tree.root.as_ref().expect("No root found").data.clone();
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
         for leaf in data.iter() {
-            let leaf_hash = keccak256(leaf).expect("Keccak error.");
+            let leaf_hash = leaf_hash(leaf).expect("Keccak error.");
             let leaf_index = tree.locate_leaf(&leaf_hash).expect("Failed to locate leaf");
-            let proof = tree.generate_proof(leaf_index);
+            let proof = to_strings(
+                tree.generate_proof(leaf_index)
+                    .expect("Failed to generate proof"),
+            );
             let result = verify_proof(proof, &root, leaf);
             assert!(result.is_ok());
             assert!(result.unwrap());
         }
     }
+
+    #[test]
+    fn test_verify_standard_proof() {
+        let data = vec![
+            "0x901Ab22EdCA65188686C9742F2C88c946698bc90, 100".to_string(),
+            "0x7b95d138cD923476b6e697391DD2aA01D15BAB27, 100".to_string(),
+            "0xaBA8e3eB6D782e3B85Aa1Dd6E5B07136D4F98236, 100".to_string(),
+        ];
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
+        for leaf in data.iter() {
+            let leaf_hash = standard_leaf_hash(leaf).expect("Keccak error.");
+            let leaf_index = tree.locate_leaf(&leaf_hash).expect("Failed to locate leaf");
+            let proof = to_strings(
+                tree.generate_proof(leaf_index)
+                    .expect("Failed to generate proof"),
+            );
+            assert!(verify_standard_proof(proof.clone(), &root, leaf).unwrap());
+            assert!(!verify_proof(proof, &root, leaf).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_verify_typed_proof() {
+        let values = vec![
+            vec![
+                String::from("0x1111111111111111111111111111111111111111"),
+                String::from("5000000000000000000"),
+            ],
+            vec![
+                String::from("0x2222222222222222222222222222222222222222"),
+                String::from("2500000000000000000"),
+            ],
+        ];
+        let types = ["address", "uint256"];
+        let tree = MerkleTree::of(&values, &types).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
+        for (value_index, value) in values.iter().enumerate() {
+            let index = tree.tree_index(value_index).expect("Failed to locate leaf");
+            let proof = to_strings(
+                tree.generate_proof(index)
+                    .expect("Failed to generate proof"),
+            );
+            assert!(verify_typed_proof(proof.clone(), &root, &types, value).unwrap());
+            assert!(!verify_typed_proof(proof, &root, &types, &values[1 - value_index]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_verify_multi_proof() {
+        let data = test_values(9);
+        let tree = MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data;
+        let indices: Vec<usize> = ["0x02", "0x03", "0x07", "0x09"]
+            .iter()
+            .map(|leaf| {
+                let leaf_hash = standard_leaf_hash(leaf).expect("Keccak error.");
+                tree.locate_leaf(&leaf_hash).expect("Failed to locate leaf")
+            })
+            .collect();
+        for count in 0..=indices.len() {
+            let multi_proof = tree
+                .generate_multi_proof(&indices[..count])
+                .expect("Failed to generate multiproof");
+            assert!(verify_multi_proof(&multi_proof, &root));
+        }
+
+        let mut tampered = tree
+            .generate_multi_proof(&indices)
+            .expect("Failed to generate multiproof");
+        let leaf = std::mem::replace(&mut tampered.leaves[0], root);
+        assert!(!verify_multi_proof(&tampered, &root));
+        tampered.leaves[0] = leaf;
+        assert!(verify_multi_proof(&tampered, &root));
+        tampered.proof_flags.push(false);
+        assert!(!verify_multi_proof(&tampered, &root));
+    }
+
+    #[test]
+    fn test_verify_positional_proof() {
+        for count in 1..=9 {
+            let data = test_values(count);
+            for tree in [
+                MerkleTree::new(&data).expect("Failed to create Merkle Tree"),
+                MerkleTree::new_standard(&data).expect("Failed to create Merkle Tree"),
+            ] {
+                let tree = tree.with_pair_hashing(PairHashing::Positional);
+                let root = tree.root.as_ref().expect("No root found").data.to_string();
+                for value_index in 0..count {
+                    let index = tree.tree_index(value_index).expect("Failed to locate leaf");
+                    let proof = tree
+                        .generate_directed_proof(index)
+                        .expect("Failed to generate proof");
+                    let leaf = tree.graph[petgraph::graph::NodeIndex::new(index)];
+                    let siblings = to_strings(proof.siblings);
+                    assert!(
+                        verify_positional_proof(siblings.clone(), &proof.sides, &root, &leaf)
+                            .unwrap()
+                    );
+                    if let Some(first) = proof.sides.first() {
+                        let mut flipped = proof.sides.clone();
+                        flipped[0] = if *first == Side::Left {
+                            Side::Right
+                        } else {
+                            Side::Left
+                        };
+                        assert!(
+                            !verify_positional_proof(siblings.clone(), &flipped, &root, &leaf)
+                                .unwrap()
+                        );
+                        assert!(matches!(
+                            verify_positional_proof(siblings.clone(), &[], &root, &leaf),
+                            Err(MerkleError::InvalidInput(_))
+                        ));
+                    }
+                    let padded = format!(" {} ", root);
+                    assert!(
+                        verify_positional_proof(siblings, &proof.sides, &padded, &leaf).unwrap()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_proof_divergence() {
+        let data = test_values(7);
+        let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data.to_hex();
+        for (i, value) in data.iter().enumerate() {
+            let index = tree.tree_index(i).expect("Failed to locate leaf");
+            let proof: Vec<String> = tree
+                .generate_proof(index)
+                .unwrap()
+                .iter()
+                .map(Hash32::to_hex)
+                .collect();
+            let expected = tree.proof_path(index).unwrap();
+            let leaf = leaf_hash(value).unwrap();
+
+            let check = check_proof(&proof, &root, &leaf).unwrap();
+            assert!(check.is_valid());
+            assert_eq!(check.path(), expected);
+            assert_eq!(check.divergence(&expected), None);
+
+            for step in 0..proof.len() {
+                let mut tampered = proof.clone();
+                tampered[step] = Hash32::default().to_hex();
+                let check = check_proof(&tampered, &root, &leaf).unwrap();
+                assert!(!check.is_valid());
+                assert_eq!(check.divergence(&expected), Some(step + 1));
+            }
+            let check = check_proof(&proof, &root, &Hash32::default()).unwrap();
+            assert_eq!(check.divergence(&expected), Some(0));
+        }
+    }
+
+    #[test]
+    fn test_check_proof_rejects_malformed_hashes() {
+        let data = vec!["0xabc".to_string(), "0xdef".to_string()];
+        let tree = MerkleTree::new(&data).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
+        let leaf = leaf_hash("0xabc").unwrap();
+        for sibling in ["", "0", "ab", "0x", "0xzz"] {
+            assert!(matches!(
+                check_proof(&[sibling], &root, &leaf),
+                Err(MerkleError::InvalidHex(_))
+            ));
+            assert!(verify_proof(vec![sibling.to_string()], &root, "0xabc").is_err());
+        }
+        assert!(matches!(
+            check_positional_proof(&["ab"; 2], &[Side::Left], &root, &leaf),
+            Err(MerkleError::InvalidInput(_))
+        ));
+    }
+
+    fn assert_verifies_with_hasher<H: MerkleHasher>() {
+        let data = test_values(5);
+        let tree = MerkleTree::<H>::new_with_hasher(&data).expect("Failed to create Merkle Tree");
+        let standard =
+            MerkleTree::<H>::new_standard_with_hasher(&data).expect("Failed to create Merkle Tree");
+        let root = tree.root.as_ref().expect("No root found").data.to_string();
+        let standard_root = standard
+            .root
+            .as_ref()
+            .expect("No root found")
+            .data
+            .to_string();
+        for leaf in &data {
+            let index = tree.locate_value(&[leaf]).expect("Failed to locate leaf");
+            let proof = to_strings(
+                tree.generate_proof(index)
+                    .expect("Failed to generate proof"),
+            );
+            assert!(verify_proof_with_hasher::<H>(proof.clone(), &root, leaf).unwrap());
+            assert!(!verify_proof(proof, &root, leaf).unwrap());
+
+            let index = standard
+                .locate_value(&[leaf])
+                .expect("Failed to locate leaf");
+            let proof = to_strings(
+                standard
+                    .generate_proof(index)
+                    .expect("Failed to generate proof"),
+            );
+            assert!(verify_standard_proof_with_hasher::<H>(proof, &standard_root, leaf).unwrap());
+        }
+        let multi_proof = standard
+            .generate_multi_proof(&[4, 6, 7])
+            .expect("Failed to generate multiproof");
+        let root = standard.root.as_ref().expect("No root found").data;
+        assert!(verify_multi_proof_with_hasher::<H>(&multi_proof, &root));
+    }
+
+    #[test]
+    fn test_verify_proof_with_other_hashers() {
+        assert_verifies_with_hasher::<Sha256>();
+        assert_verifies_with_hasher::<Blake2b>();
+        assert_verifies_with_hasher::<Blake3>();
+    }
 }